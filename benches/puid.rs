@@ -1,5 +1,28 @@
 use criterion::{black_box, criterion_group, criterion_main, Criterion};
 use puid::Puid;
+use std::{
+    alloc::{GlobalAlloc, Layout, System},
+    sync::atomic::{AtomicUsize, Ordering},
+};
+
+// Counts the allocations of the benchmarks
+struct Counting;
+
+static ALLOCATIONS: AtomicUsize = AtomicUsize::new(0);
+
+unsafe impl GlobalAlloc for Counting {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        ALLOCATIONS.fetch_add(1, Ordering::Relaxed);
+        System.alloc(layout)
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        System.dealloc(ptr, layout)
+    }
+}
+
+#[global_allocator]
+static ALLOCATOR: Counting = Counting;
 
 fn bench_puid_creation(c: &mut Criterion) {
     c.bench_function("create puid", |b| {
@@ -10,5 +33,52 @@ fn bench_puid_creation(c: &mut Criterion) {
     });
 }
 
-criterion_group!(benches, bench_puid_creation);
+fn bench_puid_bulk_random(c: &mut Criterion) {
+    let mut group = c.benchmark_group("entropy 64");
+    for bulk in [false, true] {
+        let builder = Puid::builder()
+            .prefix("test")
+            .unwrap()
+            .entropy(64)
+            .bulk_random(bulk);
+        let name = if bulk { "bulk random" } else { "per character" };
+        group.bench_function(name, |b| {
+            b.iter(|| black_box((&builder).into_iter().next()));
+        });
+    }
+    group.finish();
+}
+
+fn bench_puid_allocations(c: &mut Criterion) {
+    let builder = Puid::builder().prefix("test").unwrap().entropy(128);
+    let before = ALLOCATIONS.load(Ordering::Relaxed);
+    for id in (&builder).into_iter().take(1000) {
+        black_box(id.unwrap());
+    }
+    let per_id = (ALLOCATIONS.load(Ordering::Relaxed) - before) as f64 / 1000.0;
+    println!("entropy 128: {per_id} allocations per puid");
+
+    c.bench_function("entropy 128", |b| {
+        b.iter(|| black_box((&builder).into_iter().next()));
+    });
+}
+
+fn bench_puid_ephemeral(c: &mut Criterion) {
+    let mut group = c.benchmark_group("ephemeral");
+    group.bench_function("build", |b| {
+        b.iter(|| black_box(Puid::builder().prefix("test").unwrap().build().unwrap()));
+    });
+    group.bench_function("ephemeral_u64", |b| {
+        b.iter(|| black_box(Puid::ephemeral_u64()));
+    });
+    group.finish();
+}
+
+criterion_group!(
+    benches,
+    bench_puid_creation,
+    bench_puid_bulk_random,
+    bench_puid_allocations,
+    bench_puid_ephemeral
+);
 criterion_main!(benches);
@@ -0,0 +1,104 @@
+#[cfg(feature = "std")]
+use crate::puid::time;
+use alloc::sync::Arc;
+use core::sync::atomic::{AtomicU64, Ordering};
+
+/// A source of the current time for the timestamps of IDs.
+///
+/// Closures returning milliseconds are clocks too, e.g. `|| 1651312057000`
+/// for a fixed time or one reading an atomic for a manually advanced one.
+pub trait Clock: Send + Sync {
+    /// Returns the current time in milliseconds since the UNIX epoch.
+    fn now_millis(&self) -> u128;
+}
+
+/// The system clock, read with `SystemTime::now()`.
+#[cfg(feature = "std")]
+#[derive(Clone, Copy, Debug, Default)]
+pub struct SystemClock;
+
+#[cfg(feature = "std")]
+impl Clock for SystemClock {
+    fn now_millis(&self) -> u128 {
+        time()
+    }
+}
+
+/// A virtual clock advanced on demand, e.g. for simulations, replays or
+/// tests of ID ordering over time.
+///
+/// Clones share the same time, so a clone handed to `PuidBuilder::clock`
+/// follows the original. The time never goes backwards.
+#[derive(Clone, Debug, Default)]
+pub struct VirtualClock(Arc<AtomicU64>);
+
+impl VirtualClock {
+    /// Creates a virtual clock starting at `ms` milliseconds since the UNIX
+    /// epoch.
+    #[must_use]
+    pub fn new(ms: u64) -> Self {
+        Self(Arc::new(AtomicU64::new(ms)))
+    }
+
+    /// Advances the clock by `ms` milliseconds, saturating at `u64::MAX`.
+    pub fn advance(&self, ms: u64) {
+        let _ = self
+            .0
+            .fetch_update(Ordering::SeqCst, Ordering::SeqCst, |now| {
+                Some(now.saturating_add(ms))
+            });
+    }
+
+    /// Moves the clock to `ms` milliseconds since the UNIX epoch, unless it
+    /// already reads a later time.
+    pub fn set(&self, ms: u64) {
+        self.0.fetch_max(ms, Ordering::SeqCst);
+    }
+}
+
+impl Clock for VirtualClock {
+    fn now_millis(&self) -> u128 {
+        u128::from(self.0.load(Ordering::SeqCst))
+    }
+}
+
+impl<F: Fn() -> u128 + Send + Sync> Clock for F {
+    fn now_millis(&self) -> u128 {
+        self()
+    }
+}
+
+#[cfg(all(test, feature = "std"))]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn clock_test() {
+        let before = time();
+        assert!(SystemClock.now_millis() >= before);
+        assert_eq!((|| 1_651_312_057_000).now_millis(), 1_651_312_057_000);
+    }
+
+    #[test]
+    fn virtual_clock_test() {
+        let clock = VirtualClock::new(1_651_312_057_000);
+        let builder = crate::Puid::builder()
+            .prefix("foo")
+            .unwrap()
+            .clock(clock.clone());
+        let timestamp = || crate::Puid::timestamp_of(&builder.generate().unwrap()).unwrap();
+        assert_eq!(timestamp(), 1_651_312_057_000);
+
+        clock.advance(1500);
+        let a = builder.generate().unwrap();
+        assert_eq!(crate::Puid::timestamp_of(&a).unwrap(), 1_651_312_058_500);
+        clock.set(1_700_000_000_000);
+        let b = builder.generate().unwrap();
+        assert_eq!(timestamp(), 1_700_000_000_000);
+        assert!(a < b);
+
+        // Setting an earlier time keeps the clock monotonic
+        clock.set(0);
+        assert_eq!(clock.now_millis(), 1_700_000_000_000);
+    }
+}
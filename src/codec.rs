@@ -0,0 +1,138 @@
+use crate::{
+    errors::{PuidError, PuidResult},
+    Puid, PuidBuilder, PuidParts,
+};
+use alloc::{
+    string::{String, ToString},
+    vec::Vec,
+};
+
+// Characters available as prefix codes
+const CODES: &str = "0123456789abcdefghijklmnopqrstuvwxyz";
+
+/// A mapping between a small set of prefixes and single-character codes.
+///
+/// Codes are assigned in registration order from the base-36 digits, so a
+/// codec holds up to 36 prefixes and must be registered identically wherever
+/// IDs are decoded.
+#[derive(Clone, Debug, Default)]
+pub struct PrefixCodec {
+    // Registered prefixes along with their codes
+    entries: Vec<(String, String)>,
+}
+
+impl PrefixCodec {
+    /// Creates an empty codec.
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Registers a prefix, returning its code or the existing one if it was
+    /// already registered.
+    ///
+    /// Returns `PuidError::InvalidPrefix` if the prefix is empty or not
+    /// alphanumeric, or if no code is left.
+    pub fn register(&mut self, prefix: &str) -> PuidResult<char> {
+        if prefix.is_empty() || !prefix.chars().all(|c| c.is_ascii_alphanumeric()) {
+            return Err(PuidError::InvalidPrefix);
+        }
+        if let Some(code) = self.encode(prefix) {
+            return Ok(code);
+        }
+        let code = CODES
+            .chars()
+            .nth(self.entries.len())
+            .ok_or(PuidError::InvalidPrefix)?;
+        self.entries.push((prefix.to_string(), code.to_string()));
+        Ok(code)
+    }
+
+    /// Returns the code of a registered prefix.
+    #[must_use]
+    pub fn encode(&self, prefix: &str) -> Option<char> {
+        self.code_of(prefix).and_then(|code| code.chars().next())
+    }
+
+    /// Returns the prefix registered for a code.
+    #[must_use]
+    pub fn decode(&self, code: &str) -> Option<&str> {
+        self.entries
+            .iter()
+            .find(|(_, c)| c == code)
+            .map(|(prefix, _)| prefix.as_str())
+    }
+
+    /// Parses an ID with a coded prefix, reporting the full prefix.
+    ///
+    /// Returns `PuidError::Malformed` if the ID does not match the layout or
+    /// its code is not registered.
+    pub fn parse(&self, id: &str) -> PuidResult<PuidParts> {
+        let mut parts = Puid::parse_lenient(id)?;
+        parts.prefix = self
+            .decode(&parts.prefix)
+            .ok_or(PuidError::Malformed)?
+            .to_string();
+        Ok(parts)
+    }
+
+    /// Returns the code of a registered prefix as a string slice.
+    fn code_of(&self, prefix: &str) -> Option<&str> {
+        self.entries
+            .iter()
+            .find(|(p, _)| p == prefix)
+            .map(|(_, code)| code.as_str())
+    }
+}
+
+impl<'a> PuidBuilder<'a> {
+    /// Sets the prefix to the code of `prefix` in `codec`, shortening IDs
+    /// of a known small set of types to a single prefix character.
+    ///
+    /// Returns `PuidError::InvalidPrefix` if the prefix is not registered.
+    pub fn coded_prefix(self, codec: &'a PrefixCodec, prefix: &str) -> PuidResult<Self> {
+        let code = codec.code_of(prefix).ok_or(PuidError::InvalidPrefix)?;
+        self.prefix(code)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloc::format;
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn prefix_codec_test() {
+        let mut codec = PrefixCodec::new();
+        assert_eq!(codec.register("user").unwrap(), '0');
+        assert_eq!(codec.register("order").unwrap(), '1');
+        assert_eq!(codec.register("invoice").unwrap(), '2');
+        assert_eq!(codec.register("order").unwrap(), '1');
+        assert!(codec.register("in_voice").is_err());
+
+        let id = Puid::builder()
+            .coded_prefix(&codec, "order")
+            .unwrap()
+            .build()
+            .unwrap();
+        assert!(id.starts_with("1_"));
+        assert_eq!(codec.parse(&id).unwrap().prefix, "order");
+
+        assert!(Puid::builder().coded_prefix(&codec, "cart").is_err());
+        let id = Puid::builder().prefix("z").unwrap().build().unwrap();
+        assert!(codec.parse(&id).is_err());
+    }
+
+    #[test]
+    fn prefix_codec_full_test() {
+        let mut codec = PrefixCodec::new();
+        for i in 0..36 {
+            codec.register(&format!("p{i}")).unwrap();
+        }
+        assert!(matches!(
+            codec.register("extra"),
+            Err(PuidError::InvalidPrefix)
+        ));
+    }
+}
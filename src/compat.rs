@@ -0,0 +1,78 @@
+use crate::{Puid, PuidBuilder};
+
+/// Whether the IDs of two builder configurations can be read by each other.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Compatibility {
+    /// Whether the second configuration can parse the IDs of the first.
+    pub forward: bool,
+    /// Whether the first configuration can parse the IDs of the second.
+    pub backward: bool,
+}
+
+impl Compatibility {
+    /// Returns whether the IDs of both configurations are interchangeable.
+    #[must_use]
+    pub fn is_full(&self) -> bool {
+        self.forward && self.backward
+    }
+}
+
+impl Puid {
+    /// Compares two builder configurations, reporting whether each one can
+    /// parse the IDs of the other, e.g. before changing the configuration of
+    /// a service with stored IDs.
+    ///
+    /// Both must share the separator and the encoding of the numeric fields,
+    /// while a configuration reads the IDs of a layout version up to its own.
+    /// The prefix and entropy do not affect parsing.
+    #[must_use]
+    pub fn compatible(a: &PuidBuilder, b: &PuidBuilder) -> Compatibility {
+        let shared =
+            a.separator_char() == b.separator_char() && a.field_digits() == b.field_digits();
+        let reads = |reader: &PuidBuilder, writer: &PuidBuilder| match (
+            reader.layout_version(),
+            writer.layout_version(),
+        ) {
+            (Some(reader), Some(writer)) => shared && writer <= reader,
+            _ => false,
+        };
+
+        Compatibility {
+            forward: reads(b, a),
+            backward: reads(a, b),
+        }
+    }
+}
+
+#[cfg(all(test, feature = "std"))]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn compatible_test() {
+        let old = Puid::builder().prefix("foo").unwrap();
+        let new = Puid::builder().prefix("foo").unwrap().entropy(24);
+        assert!(Puid::compatible(&old, &new).is_full());
+
+        let new = Puid::builder().prefix("foo").unwrap().separator('-');
+        assert_eq!(
+            Puid::compatible(&old, &new),
+            Compatibility {
+                forward: false,
+                backward: false,
+            }
+        );
+
+        let new = Puid::builder().prefix("foo").unwrap().length_header(true);
+        assert_eq!(
+            Puid::compatible(&old, &new),
+            Compatibility {
+                forward: true,
+                backward: false,
+            }
+        );
+
+        let new = Puid::builder().prefix("foo").unwrap().readable();
+        assert!(!Puid::compatible(&old, &new).forward);
+    }
+}
@@ -0,0 +1,77 @@
+use crate::{
+    errors::PuidResult,
+    format::Fields,
+    puid::{fnv1a_64, to_base62, DEFAULT_ENTROPY},
+    Puid,
+};
+use alloc::string::String;
+
+/// Characters of the correlation mark ending the random sequence, enough
+/// for a 64-bit hash in base-62.
+const MARK_LEN: usize = 11;
+/// Block index hashed into the correlation mark.
+const MARK_BLOCK: u64 = 0x636f_7272;
+
+impl Puid {
+    /// Generates two IDs sharing a hidden correlation, e.g. to link a request
+    /// and its response without exposing the relationship in either ID.
+    ///
+    /// Both IDs end with 11 more characters than the default random sequence:
+    /// random ones in the first, and a hash of those in the second, so each
+    /// ID alone still looks random. `Puid::correlates` verifies a pair. The
+    /// hash is not keyed, so anyone aware of the scheme can link the IDs.
+    pub fn correlated_pair(prefix_a: &str, prefix_b: &str) -> PuidResult<(String, String)> {
+        let entropy = usize::from(DEFAULT_ENTROPY) + MARK_LEN;
+        let a = Puid::builder().prefix(prefix_a)?.entropy(entropy).build()?;
+        let mut b = Puid::builder().prefix(prefix_b)?.entropy(entropy).build()?;
+        b.truncate(b.len() - MARK_LEN);
+        b.push_str(&mark(&a[a.len() - MARK_LEN..]));
+        Ok((a, b))
+    }
+
+    /// Returns whether two IDs were generated together by
+    /// `Puid::correlated_pair`, in either order.
+    ///
+    /// Returns `PuidError::Malformed` if either ID does not match the layout.
+    pub fn correlates(a: &str, b: &str) -> PuidResult<bool> {
+        let (a, b) = (Fields::split(a)?.random, Fields::split(b)?.random);
+        let linked = |token: &str, marked: &str| match (tail(token), tail(marked)) {
+            (Some(token), Some(marked)) => mark(token) == marked,
+            _ => false,
+        };
+        Ok(linked(a, b) || linked(b, a))
+    }
+}
+
+/// Returns the characters of a random sequence the correlation mark spans.
+fn tail(random: &str) -> Option<&str> {
+    random.len().checked_sub(MARK_LEN).map(|at| &random[at..])
+}
+
+/// Returns the correlation mark of the random characters ending an ID.
+fn mark(token: &str) -> String {
+    to_base62(
+        &fnv1a_64(MARK_BLOCK, token.as_bytes()).to_be_bytes(),
+        MARK_LEN,
+    )
+}
+
+#[cfg(all(test, feature = "std"))]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn correlated_pair_test() {
+        let (request, response) = Puid::correlated_pair("req", "res").unwrap();
+        assert!(request.starts_with("req_1"));
+        assert!(response.starts_with("res_1"));
+        assert!(Puid::correlates(&request, &response).unwrap());
+        assert!(Puid::correlates(&response, &request).unwrap());
+
+        let (other, _) = Puid::correlated_pair("req", "res").unwrap();
+        assert!(!Puid::correlates(&other, &response).unwrap());
+        let unrelated = Puid::builder().prefix("res").unwrap().build().unwrap();
+        assert!(!Puid::correlates(&request, &unrelated).unwrap());
+        assert!(Puid::correlates(&request, "res").is_err());
+    }
+}
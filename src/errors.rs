@@ -1,35 +1,283 @@
+use crate::MAX_ENTROPY;
+
 /// Type for work with errors produced by Puid.
 #[derive(Clone, Debug)]
 pub enum PuidError {
-    /// Error occurred when the prefix has an invalid format or empty.
+    /// Error occurred when the prefix is empty, too short or long, or not alphanumeric.
     InvalidPrefix,
+    /// Error occurred when the ID would exceed the configured maximum length.
+    TooLong,
+    /// Error occurred when a string does not have the layout of a Puid.
+    Malformed,
+    /// Error occurred when the separator can also appear in the prefix or the encoded fields.
+    SeparatorCollision,
+    /// Error occurred when an alphabet has a single character, repeats any or does not suit the options.
+    InvalidAlphabet,
+    /// Error occurred when a generated ID repeats a previously generated one.
+    Duplicate,
+    /// Error occurred when an ID does not have the expected prefix.
+    PrefixMismatch,
+    /// Error occurred when retrying a duplicate grows the entropy past `MAX_ENTROPY`.
+    EntropyTooLarge,
+    /// Error occurred when the separator is not a printable ASCII character.
+    InvalidSeparator,
+    /// Error occurred when a sortable configuration includes an option breaking the lexical order.
+    Unsortable,
+    /// Error occurred when a shard count of zero is requested.
+    InvalidShardCount,
+    /// Error occurred when the minimum of an entropy range exceeds its maximum.
+    InvalidEntropyRange,
+    /// Error occurred when an alphabet has no characters.
+    EmptyAlphabet,
+    /// Error occurred when a batch spec lists the same prefix more than once.
+    DuplicatePrefix,
+    /// Error occurred when a locale tag is not a language with an optional region.
+    InvalidLocale,
+    /// Error occurred when the configuration combines options that cannot be used together.
+    ConflictingOptions,
+    /// Error occurred when a clock or a seed is missing without `std`.
+    MissingSource,
+    /// Error occurred when the entropy falls outside the range allowed, up to `MAX_ENTROPY` or 255 when self-describing.
+    EntropyOutOfRange,
 }
 
 /// A `Result` alias type for Puid.
 pub type PuidResult<T> = Result<T, PuidError>;
 
-impl std::fmt::Display for PuidError {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+impl core::fmt::Display for PuidError {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
         match self {
             PuidError::InvalidPrefix => {
-                write!(f, "Prefix cannot be longer than 8 characters with non-alphanumeric characters or non empty.")
+                write!(f, "Prefix must be alphanumeric and within the configured length bounds, 1 to 8 characters by default.")
+            }
+            PuidError::TooLong => {
+                write!(f, "ID exceeds the configured maximum length.")
+            }
+            PuidError::Malformed => {
+                write!(f, "ID does not match the expected layout.")
+            }
+            PuidError::SeparatorCollision => {
+                write!(
+                    f,
+                    "Separator collides with a character of the prefix or encoded fields."
+                )
+            }
+            PuidError::InvalidAlphabet => {
+                write!(
+                    f,
+                    "Alphabet must have at least 2 characters without repetitions and suit the configured options."
+                )
+            }
+            PuidError::Duplicate => {
+                write!(f, "Generated ID collides with a previously generated one.")
+            }
+            PuidError::PrefixMismatch => {
+                write!(f, "ID prefix does not match the expected one.")
+            }
+            PuidError::EntropyTooLarge => {
+                write!(f, "Entropy exceeds the maximum number of random characters the configuration allows.")
+            }
+            PuidError::InvalidSeparator => {
+                write!(f, "Separator must be a printable ASCII character.")
+            }
+            PuidError::Unsortable => {
+                write!(f, "Configuration does not keep IDs in lexical time order.")
+            }
+            PuidError::InvalidShardCount => {
+                write!(f, "Shard count must be at least 1.")
+            }
+            PuidError::InvalidEntropyRange => {
+                write!(f, "Entropy range minimum cannot exceed its maximum.")
+            }
+            PuidError::EmptyAlphabet => {
+                write!(f, "Alphabet cannot be empty.")
+            }
+            PuidError::DuplicatePrefix => {
+                write!(f, "Batch spec lists the same prefix more than once.")
+            }
+            PuidError::InvalidLocale => {
+                write!(
+                    f,
+                    "Locale must be a 2 or 3 letter language with an optional 2 letter region."
+                )
+            }
+            PuidError::ConflictingOptions => {
+                write!(
+                    f,
+                    "Configuration combines options that cannot be used together."
+                )
+            }
+            PuidError::MissingSource => {
+                write!(
+                    f,
+                    "Configuration needs a clock and a seed without the `std` feature."
+                )
+            }
+            PuidError::EntropyOutOfRange => {
+                write!(
+                    f,
+                    "Entropy must be at most {MAX_ENTROPY} random characters, or 255 when self-describing."
+                )
             }
         }
     }
 }
 
+#[cfg(feature = "std")]
 impl std::error::Error for PuidError {}
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use alloc::string::ToString;
 
     #[test]
     fn puid_error_invalid_prefix_test() {
         let err = PuidError::InvalidPrefix;
         assert_eq!(
             err.to_string(),
-            "Prefix cannot be longer than 8 characters with non-alphanumeric characters or non empty."
+            "Prefix must be alphanumeric and within the configured length bounds, 1 to 8 characters by default."
+        )
+    }
+
+    #[test]
+    fn puid_error_too_long_test() {
+        let err = PuidError::TooLong;
+        assert_eq!(err.to_string(), "ID exceeds the configured maximum length.")
+    }
+
+    #[test]
+    fn puid_error_malformed_test() {
+        let err = PuidError::Malformed;
+        assert_eq!(err.to_string(), "ID does not match the expected layout.")
+    }
+
+    #[test]
+    fn puid_error_separator_collision_test() {
+        let err = PuidError::SeparatorCollision;
+        assert_eq!(
+            err.to_string(),
+            "Separator collides with a character of the prefix or encoded fields."
+        )
+    }
+
+    #[test]
+    fn puid_error_invalid_alphabet_test() {
+        let err = PuidError::InvalidAlphabet;
+        assert_eq!(
+            err.to_string(),
+            "Alphabet must have at least 2 characters without repetitions and suit the configured options."
+        )
+    }
+
+    #[test]
+    fn puid_error_duplicate_test() {
+        let err = PuidError::Duplicate;
+        assert_eq!(
+            err.to_string(),
+            "Generated ID collides with a previously generated one."
+        )
+    }
+
+    #[test]
+    fn puid_error_prefix_mismatch_test() {
+        let err = PuidError::PrefixMismatch;
+        assert_eq!(
+            err.to_string(),
+            "ID prefix does not match the expected one."
+        )
+    }
+
+    #[test]
+    fn puid_error_entropy_too_large_test() {
+        let err = PuidError::EntropyTooLarge;
+        assert_eq!(
+            err.to_string(),
+            "Entropy exceeds the maximum number of random characters the configuration allows."
+        )
+    }
+
+    #[test]
+    fn puid_error_invalid_separator_test() {
+        let err = PuidError::InvalidSeparator;
+        assert_eq!(
+            err.to_string(),
+            "Separator must be a printable ASCII character."
+        )
+    }
+
+    #[test]
+    fn puid_error_unsortable_test() {
+        let err = PuidError::Unsortable;
+        assert_eq!(
+            err.to_string(),
+            "Configuration does not keep IDs in lexical time order."
+        )
+    }
+
+    #[test]
+    fn puid_error_invalid_shard_count_test() {
+        let err = PuidError::InvalidShardCount;
+        assert_eq!(err.to_string(), "Shard count must be at least 1.")
+    }
+
+    #[test]
+    fn puid_error_invalid_entropy_range_test() {
+        let err = PuidError::InvalidEntropyRange;
+        assert_eq!(
+            err.to_string(),
+            "Entropy range minimum cannot exceed its maximum."
+        )
+    }
+
+    #[test]
+    fn puid_error_empty_alphabet_test() {
+        let err = PuidError::EmptyAlphabet;
+        assert_eq!(err.to_string(), "Alphabet cannot be empty.")
+    }
+
+    #[test]
+    fn puid_error_duplicate_prefix_test() {
+        let err = PuidError::DuplicatePrefix;
+        assert_eq!(
+            err.to_string(),
+            "Batch spec lists the same prefix more than once."
+        )
+    }
+
+    #[test]
+    fn puid_error_invalid_locale_test() {
+        let err = PuidError::InvalidLocale;
+        assert_eq!(
+            err.to_string(),
+            "Locale must be a 2 or 3 letter language with an optional 2 letter region."
+        )
+    }
+
+    #[test]
+    fn puid_error_conflicting_options_test() {
+        let err = PuidError::ConflictingOptions;
+        assert_eq!(
+            err.to_string(),
+            "Configuration combines options that cannot be used together."
+        )
+    }
+
+    #[test]
+    fn puid_error_missing_source_test() {
+        let err = PuidError::MissingSource;
+        assert_eq!(
+            err.to_string(),
+            "Configuration needs a clock and a seed without the `std` feature."
+        )
+    }
+
+    #[test]
+    fn puid_error_entropy_out_of_range_test() {
+        let err = PuidError::EntropyOutOfRange;
+        assert_eq!(
+            err.to_string(),
+            "Entropy must be at most 1024 random characters, or 255 when self-describing."
         )
     }
 }
@@ -0,0 +1,62 @@
+use crate::{puid::DEFAULT_ENTROPY, Puid};
+
+/// The compile-time features the crate was built with.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Features {
+    /// Whether `PuidBuilder::deterministic` is available, with `testing`.
+    pub testing: bool,
+    /// Whether IDs can be signed and verified, with `hmac`.
+    pub hmac: bool,
+    /// Whether IDs can be built into an `ArrayString`, with `arrayvec`.
+    pub arrayvec: bool,
+    /// Whether `Puid::secret` draws from the OS random generator, with `secure`.
+    pub secure: bool,
+    /// Whether generation metrics are collected, with `metrics`.
+    pub metrics: bool,
+    /// Whether `PuidId` implements serde's traits, with `serde`.
+    pub serde: bool,
+    /// The default entropy of `PuidBuilder::new()`, changed with the
+    /// `default-entropy-*` features.
+    pub default_entropy: u8,
+}
+
+impl Puid {
+    /// Returns the compile-time features the crate was built with, e.g. to
+    /// log them along with a bug report.
+    #[must_use]
+    pub fn features() -> Features {
+        Features {
+            testing: cfg!(feature = "testing"),
+            hmac: cfg!(feature = "hmac"),
+            arrayvec: cfg!(feature = "arrayvec"),
+            secure: cfg!(feature = "secure"),
+            metrics: cfg!(feature = "metrics"),
+            serde: cfg!(feature = "serde"),
+            default_entropy: DEFAULT_ENTROPY,
+        }
+    }
+}
+
+#[cfg(all(test, feature = "std"))]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn features_test() {
+        let features = Puid::features();
+        assert_eq!(features.testing, cfg!(feature = "testing"));
+        assert_eq!(features.hmac, cfg!(feature = "hmac"));
+        assert_eq!(features.secure, cfg!(feature = "secure"));
+        assert_eq!(features.serde, cfg!(feature = "serde"));
+        assert_eq!(
+            usize::from(features.default_entropy),
+            Puid::builder()
+                .prefix("foo")
+                .unwrap()
+                .build()
+                .unwrap()
+                .len()
+                - 28
+        );
+    }
+}
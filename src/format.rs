@@ -0,0 +1,1311 @@
+#[cfg(feature = "std")]
+use crate::puid::time;
+use crate::{
+    errors::{PuidError, PuidResult},
+    iso::{self, ISO_TIME_LEN},
+    puid::{
+        fnv1a_64, from_base32_crockford, from_base32_readable, from_base36, to_base32_crockford,
+        to_base36, validate_with, TimeResolution, BASE_64_URL, DEFAULT_SEPARATOR, EXTENDED_VERSION,
+        FLAG_CROCKFORD, FLAG_DELIMITED, FLAG_ENTROPY, FLAG_EXPIRY, FLAG_INSTALLATION,
+        FLAG_ISO_TIME, FLAG_LENGTH_HEADER, FLAG_NAMESPACE, FLAG_NODE_ID, FLAG_NO_PID, FLAG_NO_TIME,
+        FLAG_READABLE, FLAG_RESOLUTION, FLAG_TAG, FLAG_THREAD_ID, PREFIX_MAX_LEN_LIMIT, TIME_MAX,
+        VERSION, WIDE_VERSION,
+    },
+    Puid,
+};
+use alloc::{
+    string::{String, ToString},
+    vec::Vec,
+};
+
+// Characters of the fixed-width fields following the version marker in a v1 ID
+const TIME_LEN: usize = 9;
+const COUNTER_LEN: usize = 7;
+const PID_LEN: usize = 7;
+const V1_FIELDS_LEN: usize = TIME_LEN + COUNTER_LEN + PID_LEN;
+// Characters of the optional entropy field
+const ENTROPY_LEN: usize = 2;
+// Characters of the optional thread ID field
+const THREAD_ID_LEN: usize = 7;
+// Characters of the optional tag field
+const TAG_LEN: usize = 4;
+// Characters of the optional namespace hash field
+const NAMESPACE_LEN: usize = 7;
+// Characters of the optional node ID field
+const NODE_ID_LEN: usize = 4;
+// Characters of the optional timestamp resolution field
+const RESOLUTION_LEN: usize = 1;
+// Characters of the flags field of an extended ID
+const FLAGS_LEN: usize = 2;
+// Characters of the flags field of a wide extended ID
+const WIDE_FLAGS_LEN: usize = 3;
+// Characters of the locale segment, a language with an optional region
+const LOCALE_MIN_LEN: usize = 2;
+const LOCALE_MAX_LEN: usize = 5;
+// Characters of the timestamp of a legacy ID until the year 2059
+const LEGACY_TIME_LEN: usize = 8;
+
+/// Layout information detected from an existing ID.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct FormatInfo {
+    /// The layout version, `None` for legacy IDs written before versioning.
+    pub version: Option<u8>,
+    /// The character separating the prefix from the body.
+    pub separator: char,
+    /// The likely encoding of the numeric fields.
+    pub encoding: Encoding,
+}
+
+/// Encoding of the numeric fields of an ID.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Encoding {
+    /// Lowercase base-36 digits, `0-9a-z`.
+    Base36,
+    /// Mixed case base-62 digits, `0-9A-Za-z`.
+    Base62,
+    /// Uppercase Crockford base-32 digits, `0-9A-Z` without `I`, `L`, `O`
+    /// and `U`.
+    Crockford,
+    /// Lowercase base-32 digits of `PuidBuilder::readable`, `2-9a-z` without
+    /// `l` and `o`.
+    Readable,
+}
+
+/// The fields of a v1 ID, borrowed from it.
+#[derive(Clone, Copy, Debug)]
+pub(crate) struct Fields<'s> {
+    pub(crate) prefix: &'s str,
+    pub(crate) locale: Option<&'s str>,
+    pub(crate) version: u8,
+    pub(crate) flags: u16,
+    pub(crate) crockford: bool,
+    pub(crate) readable: bool,
+    pub(crate) resolution: TimeResolution,
+    pub(crate) timestamp: &'s str,
+    pub(crate) counter: &'s str,
+    pub(crate) process_id: Option<&'s str>,
+    pub(crate) tag: Option<&'s str>,
+    pub(crate) namespace: Option<&'s str>,
+    pub(crate) node_id: Option<&'s str>,
+    pub(crate) installation: Option<&'s str>,
+    pub(crate) expiry: Option<&'s str>,
+    pub(crate) random: &'s str,
+}
+
+impl<'s> Fields<'s> {
+    /// Splits a versioned ID with base-36, Crockford base-32 or readable
+    /// base-32 fields into its fields.
+    ///
+    /// Returns `PuidError::Malformed` if the layout does not match.
+    pub(crate) fn split(id: &'s str) -> PuidResult<Self> {
+        let (prefix, separator, body) = match prefix_len(id) {
+            Some(0) => return Err(PuidError::Malformed),
+            Some(at) => {
+                let separator = id[at..].chars().next().unwrap();
+                (&id[..at], separator, &id[at + separator.len_utf8()..])
+            }
+            // An ID without a separator has no prefix either
+            None => ("", DEFAULT_SEPARATOR, id),
+        };
+        let (locale, body) = match prefix.is_empty() {
+            true => (None, body),
+            false => split_locale(body, separator),
+        };
+
+        let (version, flags, body) = match body.get(..1).and_then(from_base36) {
+            Some(v) if v == u128::from(VERSION) => (VERSION, 0, &body[1..]),
+            Some(v) if v == u128::from(EXTENDED_VERSION) || v == u128::from(WIDE_VERSION) => {
+                let version = u8::try_from(v).unwrap();
+                let len = flags_len(version);
+                let flags = body
+                    .get(1..=len)
+                    .and_then(from_base36)
+                    .ok_or(PuidError::Malformed)?;
+                (version, flags, &body[1 + len..])
+            }
+            _ => return Err(PuidError::Malformed),
+        };
+
+        let flags = u16::try_from(flags).map_err(|_| PuidError::Malformed)?;
+        let has = |flag: u16| flags & flag != 0;
+        let crockford = has(FLAG_CROCKFORD);
+        let readable = has(FLAG_READABLE);
+        let decode: fn(&str) -> Option<u128> = if crockford {
+            from_base32_crockford
+        } else if readable {
+            from_base32_readable
+        } else {
+            from_base36
+        };
+        // Both base-32 alphabets need as many digits
+        let width = |max: u128| match crockford || readable {
+            true => to_base32_crockford(max).len(),
+            false => to_base36(max).len(),
+        };
+
+        let mut rest = body;
+        // Strips the separator written before each field when delimited
+        let delimiter = |rest: &mut &'s str| {
+            if has(FLAG_DELIMITED) {
+                *rest = rest.strip_prefix(separator).ok_or(PuidError::Malformed)?;
+            }
+            Ok(())
+        };
+        let entropy = if has(FLAG_ENTROPY) {
+            delimiter(&mut rest)?;
+            decode(take(&mut rest, ENTROPY_LEN, decode)?)
+        } else {
+            None
+        };
+        let resolution = if has(FLAG_RESOLUTION) {
+            delimiter(&mut rest)?;
+            decode(take(&mut rest, RESOLUTION_LEN, decode)?)
+                .and_then(TimeResolution::from_code)
+                .ok_or(PuidError::Malformed)?
+        } else {
+            TimeResolution::Millis
+        };
+        let timestamp = if has(FLAG_NO_TIME) {
+            // An empty field where the timestamp would be
+            &rest[..0]
+        } else if has(FLAG_ISO_TIME) {
+            delimiter(&mut rest)?;
+            let field = rest.get(..ISO_TIME_LEN).ok_or(PuidError::Malformed)?;
+            iso::parse(field).ok_or(PuidError::Malformed)?;
+            rest = &rest[ISO_TIME_LEN..];
+            field
+        } else {
+            delimiter(&mut rest)?;
+            take(&mut rest, width(resolution.max()), decode)?
+        };
+        delimiter(&mut rest)?;
+        let counter = take(&mut rest, COUNTER_LEN, decode)?;
+        let process_id = if has(FLAG_NO_PID) {
+            None
+        } else {
+            delimiter(&mut rest)?;
+            Some(take(&mut rest, PID_LEN, decode)?)
+        };
+        if has(FLAG_THREAD_ID) {
+            delimiter(&mut rest)?;
+            take(&mut rest, THREAD_ID_LEN, decode)?;
+        }
+        let tag = if has(FLAG_TAG) {
+            delimiter(&mut rest)?;
+            Some(take(&mut rest, TAG_LEN, decode)?)
+        } else {
+            None
+        };
+        let namespace = if has(FLAG_NAMESPACE) {
+            delimiter(&mut rest)?;
+            Some(take(&mut rest, NAMESPACE_LEN, decode)?)
+        } else {
+            None
+        };
+        let node_id = if has(FLAG_NODE_ID) {
+            delimiter(&mut rest)?;
+            Some(take(&mut rest, NODE_ID_LEN, decode)?)
+        } else {
+            None
+        };
+        let installation = if has(FLAG_INSTALLATION) {
+            delimiter(&mut rest)?;
+            Some(take(&mut rest, width(u128::from(u64::MAX)), decode)?)
+        } else {
+            None
+        };
+        let expiry = if has(FLAG_EXPIRY) {
+            delimiter(&mut rest)?;
+            Some(take(&mut rest, width(TIME_MAX), decode)?)
+        } else {
+            None
+        };
+        delimiter(&mut rest)?;
+
+        let mut random = rest;
+        if has(FLAG_LENGTH_HEADER) {
+            let (rest, header) = random
+                .split_at_checked(random.len().saturating_sub(1))
+                .ok_or(PuidError::Malformed)?;
+            if from_base36(header) != u128::try_from(prefix.len()).ok() {
+                return Err(PuidError::Malformed);
+            }
+            random = rest;
+        }
+        let random_len = random.chars().filter(|&c| c != '-').count();
+        if entropy.is_some_and(|entropy| u128::try_from(random_len).ok() != Some(entropy)) {
+            return Err(PuidError::Malformed);
+        }
+        if !random
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_')
+        {
+            return Err(PuidError::Malformed);
+        }
+
+        Ok(Fields {
+            prefix,
+            locale,
+            version,
+            flags,
+            crockford,
+            readable,
+            resolution,
+            timestamp,
+            counter,
+            process_id,
+            tag,
+            namespace,
+            node_id,
+            installation,
+            expiry,
+            random,
+        })
+    }
+
+    /// Decodes the timestamp in milliseconds since the UNIX epoch, `None`
+    /// if the ID has none.
+    pub(crate) fn time_ms(&self) -> Option<u128> {
+        // The timestamp was validated when splitting
+        if self.flags & FLAG_NO_TIME != 0 {
+            None
+        } else if self.flags & FLAG_ISO_TIME != 0 {
+            iso::parse(self.timestamp)
+        } else {
+            self.decode(self.timestamp)
+                .map(|ticks| self.resolution.millis(ticks))
+        }
+    }
+
+    /// Decodes a numeric field in the encoding of the ID.
+    pub(crate) fn decode(&self, field: &str) -> Option<u128> {
+        if self.crockford {
+            from_base32_crockford(field)
+        } else if self.readable {
+            from_base32_readable(field)
+        } else {
+            from_base36(field)
+        }
+    }
+}
+
+/// Returns the characters of the flags field following a version marker.
+pub(crate) fn flags_len(version: u8) -> usize {
+    match version {
+        EXTENDED_VERSION => FLAGS_LEN,
+        WIDE_VERSION => WIDE_FLAGS_LEN,
+        _ => 0,
+    }
+}
+
+/// Splits the locale segment written by `PuidBuilder::locale` off the front
+/// of a body, 2 to 5 lowercase letters followed by the separator.
+fn split_locale(body: &str, separator: char) -> (Option<&str>, &str) {
+    match body.split_once(separator) {
+        Some((locale, rest))
+            if (LOCALE_MIN_LEN..=LOCALE_MAX_LEN).contains(&locale.len())
+                && locale.bytes().all(|b| b.is_ascii_lowercase()) =>
+        {
+            (Some(locale), rest)
+        }
+        _ => (None, body),
+    }
+}
+
+/// Takes a field of `len` characters from the front of `rest`, validated
+/// with `decode`.
+fn take<'s>(
+    rest: &mut &'s str,
+    len: usize,
+    decode: fn(&str) -> Option<u128>,
+) -> PuidResult<&'s str> {
+    let field = rest.get(..len).ok_or(PuidError::Malformed)?;
+    decode(field).ok_or(PuidError::Malformed)?;
+    *rest = &rest[len..];
+    Ok(field)
+}
+
+/// Returns the length of the prefix of an ID.
+///
+/// Reads the length header in constant time when the ID ends with one, and
+/// otherwise scans for the first separator.
+pub(crate) fn prefix_len(id: &str) -> Option<usize> {
+    let header = id
+        .get(id.len().saturating_sub(1)..)
+        .and_then(from_base36)
+        .and_then(|len| usize::try_from(len).ok());
+    if let Some(len) = header {
+        let bytes = id.as_bytes();
+        // A separator followed by an extended version confirms the header
+        let confirmed = len > 0
+            && bytes.get(len).is_some_and(|b| !b.is_ascii_alphanumeric())
+            && id[len..]
+                .chars()
+                .nth(1)
+                .and_then(|c| c.to_digit(36))
+                .is_some_and(|v| v == u32::from(EXTENDED_VERSION) || v == u32::from(WIDE_VERSION))
+            && bytes[..len].iter().all(u8::is_ascii_alphanumeric);
+        if confirmed {
+            return Some(len);
+        }
+    }
+    id.find(|c: char| !c.is_ascii_alphanumeric())
+}
+
+impl Puid {
+    /// Returns the prefix of an ID as a slice borrowed from it.
+    ///
+    /// Uses the length header when present and otherwise splits at the first
+    /// separator. Returns `PuidError::Malformed` if the ID has no separator
+    /// or an empty prefix.
+    pub fn prefix_of(id: &str) -> PuidResult<&str> {
+        match prefix_len(id) {
+            Some(len) if len > 0 => Ok(&id[..len]),
+            _ => Err(PuidError::Malformed),
+        }
+    }
+
+    /// Checks that an ID has the `expected` prefix and returns its body, the
+    /// part after the separator, borrowed from it.
+    ///
+    /// Returns `PuidError::PrefixMismatch` if the prefix differs and
+    /// `PuidError::Malformed` if the ID has no prefix.
+    pub fn strip_prefix<'s>(id: &'s str, expected: &str) -> PuidResult<&'s str> {
+        let prefix = Puid::prefix_of(id)?;
+        if prefix != expected {
+            return Err(PuidError::PrefixMismatch);
+        }
+        let rest = &id[prefix.len()..];
+        let body = rest.char_indices().nth(1).map_or(rest.len(), |(i, _)| i);
+        Ok(&rest[body..])
+    }
+
+    /// Returns the user-defined tag embedded in an ID.
+    ///
+    /// Returns `PuidError::Malformed` if the ID does not match the layout or
+    /// carries no tag.
+    pub fn tag_of(id: &str) -> PuidResult<u16> {
+        let fields = Fields::split(id)?;
+        fields
+            .tag
+            .and_then(|tag| fields.decode(tag))
+            .and_then(|tag| u16::try_from(tag).ok())
+            .ok_or(PuidError::Malformed)
+    }
+
+    /// Returns the client installation identifier embedded in an ID with
+    /// `PuidBuilder::installation_id`.
+    ///
+    /// Returns `PuidError::Malformed` if the ID does not match the layout or
+    /// carries no installation ID.
+    pub fn installation_of(id: &str) -> PuidResult<u64> {
+        let fields = Fields::split(id)?;
+        fields
+            .installation
+            .and_then(|installation| fields.decode(installation))
+            .and_then(|installation| u64::try_from(installation).ok())
+            .ok_or(PuidError::Malformed)
+    }
+
+    /// Returns the shard of an ID among `shards`, for consistent routing.
+    ///
+    /// Only the random sequence is hashed, with the 64-bit FNV-1a function,
+    /// so IDs generated close in time still spread evenly across shards.
+    /// Returns `PuidError::InvalidShardCount` if `shards` is zero and
+    /// `PuidError::Malformed` if the ID does not match the layout.
+    pub fn shard(id: &str, shards: u32) -> PuidResult<u32> {
+        if shards == 0 {
+            return Err(PuidError::InvalidShardCount);
+        }
+        let hash = fnv1a_64(0, Fields::split(id)?.random.as_bytes());
+        Ok(u32::try_from(hash % u64::from(shards)).unwrap())
+    }
+
+    /// Returns the timestamp embedded in an ID, in milliseconds since the
+    /// UNIX epoch.
+    ///
+    /// Returns `PuidError::Malformed` if the ID does not match the layout or
+    /// carries no timestamp.
+    pub fn timestamp_of(id: &str) -> PuidResult<u128> {
+        Fields::split(id)?.time_ms().ok_or(PuidError::Malformed)
+    }
+
+    /// Returns whether the expiry embedded in an ID, with
+    /// `PuidBuilder::expires_in`, has passed.
+    ///
+    /// Returns `PuidError::Malformed` if the ID does not match the layout or
+    /// carries no expiry.
+    #[cfg(feature = "std")]
+    pub fn is_expired(id: &str) -> PuidResult<bool> {
+        let fields = Fields::split(id)?;
+        let expiry = fields
+            .expiry
+            .and_then(|expiry| fields.decode(expiry))
+            .ok_or(PuidError::Malformed)?;
+        Ok(expiry <= time())
+    }
+
+    /// Returns the locale embedded in an ID with `PuidBuilder::locale`,
+    /// lowercase with a hyphen before the region, e.g. `pt-br`.
+    ///
+    /// Returns `PuidError::Malformed` if the ID does not match the layout or
+    /// carries no locale.
+    pub fn locale_of(id: &str) -> PuidResult<String> {
+        let locale = Fields::split(id)?.locale.ok_or(PuidError::Malformed)?;
+        // The language has 2 or 3 letters and the region 2
+        Ok(match locale.len() {
+            4 | 5 => [
+                &locale[..locale.len() - 2],
+                "-",
+                &locale[locale.len() - 2..],
+            ]
+            .concat(),
+            _ => locale.to_string(),
+        })
+    }
+
+    /// Returns whether the timestamp embedded in an ID lies within `[min,
+    /// max]` milliseconds since the UNIX epoch, e.g. to reject forged or
+    /// corrupted IDs dated 1970 or 9999.
+    ///
+    /// Returns `PuidError::Malformed` if the ID does not match the layout.
+    pub fn validate_timestamp(id: &str, min: u128, max: u128) -> PuidResult<bool> {
+        Puid::timestamp_of(id).map(|time| (min..=max).contains(&time))
+    }
+
+    /// Returns the difference between the current time and the timestamp
+    /// embedded in an ID, in milliseconds.
+    ///
+    /// A negative skew means the ID carries a timestamp in the future, e.g.
+    /// from a producer whose clock runs ahead. Returns `PuidError::Malformed`
+    /// if the ID does not match the layout.
+    #[cfg(feature = "std")]
+    pub fn clock_skew(id: &str) -> PuidResult<i128> {
+        let embedded = Puid::timestamp_of(id)?;
+        Ok(i128::try_from(time()).unwrap() - i128::try_from(embedded).unwrap())
+    }
+
+    /// Checks that a string is a well-formed versioned ID, e.g. as a cheap
+    /// guard at an API boundary, without decoding it into parts.
+    ///
+    /// Returns `PuidError::InvalidPrefix` unless the prefix is 1 to 32
+    /// alphanumeric characters, and `PuidError::Malformed` for a missing
+    /// separator, a field with characters outside its encoding, or a counter
+    /// or process ID beyond 32 bits.
+    pub fn validate_id(id: &str) -> PuidResult<()> {
+        if !validate_with(Puid::prefix_of(id)?, PREFIX_MAX_LEN_LIMIT) {
+            return Err(PuidError::InvalidPrefix);
+        }
+        let fields = Fields::split(id)?;
+        let fits = |field: &str| {
+            fields
+                .decode(field)
+                .is_some_and(|v| v <= u128::from(u32::MAX))
+        };
+        if !fits(fields.counter) || !fields.process_id.is_none_or(fits) {
+            return Err(PuidError::Malformed);
+        }
+        Ok(())
+    }
+
+    /// Checks that a string is a well-formed versioned ID, as `validate_id`
+    /// does, whose prefix is one of `known_prefixes`, compared ignoring ASCII
+    /// case when `case_insensitive` is set.
+    ///
+    /// Returns `PuidError::PrefixMismatch` for a prefix outside the list.
+    pub fn validate_id_with_policy(
+        id: &str,
+        case_insensitive: bool,
+        known_prefixes: &[&str],
+    ) -> PuidResult<()> {
+        Puid::validate_id(id)?;
+        let prefix = Puid::prefix_of(id)?;
+        let known = |candidate: &&str| {
+            if case_insensitive {
+                candidate.eq_ignore_ascii_case(prefix)
+            } else {
+                *candidate == prefix
+            }
+        };
+        if !known_prefixes.iter().any(known) {
+            return Err(PuidError::PrefixMismatch);
+        }
+        Ok(())
+    }
+
+    /// Rewrites an ID into its canonical form, e.g. to deduplicate IDs
+    /// ingested from sources which changed their case or separator.
+    ///
+    /// The prefix and the case insensitive fields are lowercased, or
+    /// uppercased for Crockford base-32 fields, and the separators become
+    /// `_`, while the random sequence is kept as is. Returns the errors of
+    /// `validate_id` for the result.
+    pub fn canonicalize(id: &str) -> PuidResult<String> {
+        let lowered = id.to_ascii_lowercase();
+        let fields = Fields::split(&lowered)?;
+        let offset = |s: &str| s.as_ptr() as usize - lowered.as_ptr() as usize;
+        let separator = lowered[fields.prefix.len()..].chars().next();
+        let mut at = fields.prefix.len() + separator.map_or(0, char::len_utf8);
+        if let Some(locale) = fields.locale {
+            at = offset(locale) + locale.len() + separator.map_or(0, char::len_utf8);
+        }
+        // The version marker stays lowercase
+        let marker = at + 1 + flags_len(fields.version);
+        let random = offset(fields.random);
+
+        let head = match separator {
+            Some(separator) => lowered[..marker].replace(separator, "_"),
+            None => lowered[..marker].to_string(),
+        };
+        let mut body = lowered[marker..random].to_string();
+        if fields.crockford {
+            body.make_ascii_uppercase();
+        }
+        if let Some(separator) = separator {
+            body = body.replace(separator, "_");
+        }
+        let canonical = [&head, &body, &id[random..]].concat();
+        Puid::validate_id(&canonical)?;
+        Ok(canonical)
+    }
+
+    /// Returns whether a string is a well-formed versioned ID, as checked by
+    /// `validate_id`.
+    #[must_use]
+    pub fn is_valid(id: &str) -> bool {
+        Puid::validate_id(id).is_ok()
+    }
+
+    /// Returns whether an ID only contains characters unreserved in URLs,
+    /// `A-Za-z0-9-._~`, so it needs no percent-encoding.
+    #[must_use]
+    pub fn is_url_safe(id: &str) -> bool {
+        id.chars()
+            .all(|c| c.is_ascii_alphanumeric() || matches!(c, '-' | '.' | '_' | '~'))
+    }
+
+    /// Decodes the body of an ID, the part after the separator, as unpadded
+    /// base64url, e.g. for IDs built with `PuidBuilder::decodable`.
+    ///
+    /// Returns `PuidError::Malformed` if the body is not a multiple of 4
+    /// base64url characters.
+    pub fn decode_body(id: &str) -> PuidResult<Vec<u8>> {
+        let prefix = Puid::prefix_of(id)?;
+        let rest = &id[prefix.len()..];
+        let body = &rest[rest.chars().next().map_or(0, char::len_utf8)..];
+        if !body.len().is_multiple_of(4) {
+            return Err(PuidError::Malformed);
+        }
+
+        let mut bytes = Vec::with_capacity(body.len() / 4 * 3);
+        for quantum in body.as_bytes().chunks(4) {
+            let mut bits = 0u32;
+            for &c in quantum {
+                let v = BASE_64_URL
+                    .find(char::from(c))
+                    .ok_or(PuidError::Malformed)?;
+                bits = bits << 6 | u32::try_from(v).unwrap();
+            }
+            bytes.extend_from_slice(&bits.to_be_bytes()[1..]);
+        }
+        Ok(bytes)
+    }
+
+    /// Shortens an ID for display to its prefix, separator and the first
+    /// `len` characters of its body, like git short hashes.
+    ///
+    /// Short forms are not guaranteed to be unique. Returns
+    /// `PuidError::Malformed` if the ID has no separator or an empty prefix.
+    pub fn short(id: &str, len: usize) -> PuidResult<String> {
+        let prefix = Puid::prefix_of(id)?;
+        let rest = &id[prefix.len()..];
+        let body = rest.char_indices().nth(1).map_or(rest.len(), |(i, _)| i);
+        let end = rest[body..]
+            .char_indices()
+            .nth(len)
+            .map_or(rest.len(), |(i, _)| body + i);
+        Ok(id[..prefix.len() + end].to_string())
+    }
+
+    /// Masks an ID for display, like a credit card number, replacing every
+    /// character with `*` except the last `keep_last` ones and, when
+    /// `keep_prefix` is set, the prefix and separator.
+    ///
+    /// The masked form keeps the length of the ID. Strings without a prefix
+    /// are masked whole.
+    #[must_use]
+    pub fn mask(id: &str, keep_prefix: bool, keep_last: usize) -> String {
+        let head = match Puid::prefix_of(id) {
+            Ok(prefix) if keep_prefix => {
+                prefix.len() + id[prefix.len()..].chars().next().map_or(0, char::len_utf8)
+            }
+            _ => 0,
+        };
+        let masked = id[head..].chars().count().saturating_sub(keep_last);
+        let mut result = String::with_capacity(id.len());
+        result.push_str(&id[..head]);
+        for (i, c) in id[head..].chars().enumerate() {
+            result.push(if i < masked { '*' } else { c });
+        }
+        result
+    }
+
+    /// Returns whether two v1 IDs were generated by the same process, by
+    /// comparing their process ID fields.
+    ///
+    /// Returns `PuidError::Malformed` if either ID does not match the layout
+    /// or omits the process ID.
+    pub fn same_origin(a: &str, b: &str) -> PuidResult<bool> {
+        let pid = |id| {
+            let fields = Fields::split(id)?;
+            fields
+                .process_id
+                .and_then(|pid| fields.decode(pid))
+                .ok_or(PuidError::Malformed)
+        };
+        Ok(pid(a)? == pid(b)?)
+    }
+
+    /// Returns whether two IDs could collide, i.e. they share their prefix
+    /// and counter and their timestamps lie within `window_ms` of each other,
+    /// e.g. to pick the pairs worth comparing in full when deduplicating.
+    ///
+    /// Returns `PuidError::Malformed` if either ID does not match the layout
+    /// or carries no timestamp.
+    pub fn potential_duplicate(a: &str, b: &str, window_ms: u128) -> PuidResult<bool> {
+        let (a, b) = (Fields::split(a)?, Fields::split(b)?);
+        let time = |fields: &Fields<'_>| fields.time_ms().ok_or(PuidError::Malformed);
+        let close = time(&a)?.abs_diff(time(&b)?) <= window_ms;
+        Ok(a.prefix == b.prefix && close && a.decode(a.counter) == b.decode(b.counter))
+    }
+
+    /// Detects the layout version, separator and likely encoding of an ID.
+    ///
+    /// Versioned IDs start their body with the version digit, while legacy
+    /// IDs start it with a base-36 timestamp, which begins with a letter for
+    /// any time after 1983. IDs without a separator have no prefix, and are
+    /// reported with the default separator. Returns `PuidError::Malformed` if
+    /// the ID starts with a separator or has a body too short for its layout.
+    pub fn detect_format(id: &str) -> PuidResult<FormatInfo> {
+        let (body, separator) = match id.char_indices().find(|(_, c)| !c.is_ascii_alphanumeric()) {
+            Some((0, _)) => return Err(PuidError::Malformed),
+            Some((at, separator)) => (&id[at + separator.len_utf8()..], separator),
+            // An ID without a separator has no prefix either
+            None => (id, DEFAULT_SEPARATOR),
+        };
+        let (_, body) = split_locale(body, separator);
+
+        let (version, fields, encoding) = match body.chars().next() {
+            Some(c) if c.is_ascii_digit() => {
+                let version = c.to_digit(10).and_then(|v| u8::try_from(v).ok());
+                let mut start = 1;
+                let mut len = V1_FIELDS_LEN;
+                let mut encoding = None;
+                if let Some(version @ (EXTENDED_VERSION | WIDE_VERSION)) = version {
+                    let flags = body.get(1..=flags_len(version)).and_then(from_base36);
+                    let has = |flag: u16| flags.is_some_and(|flags| flags & u128::from(flag) != 0);
+                    start += flags_len(version);
+                    if has(FLAG_CROCKFORD) {
+                        encoding = Some(Encoding::Crockford);
+                    } else if has(FLAG_READABLE) {
+                        encoding = Some(Encoding::Readable);
+                    }
+                    let width = |max: u128| match encoding {
+                        Some(_) => to_base32_crockford(max).len(),
+                        None => to_base36(max).len(),
+                    };
+                    // The separator before each field when delimited
+                    let d = usize::from(has(FLAG_DELIMITED));
+                    len += 3 * d;
+                    if has(FLAG_NO_PID) {
+                        len -= PID_LEN + d;
+                    }
+                    // The ISO-8601 timestamp says nothing about the encoding
+                    if has(FLAG_ISO_TIME) {
+                        start += ISO_TIME_LEN + d;
+                        len -= TIME_LEN + d;
+                    }
+                    if has(FLAG_NO_TIME) {
+                        len -= TIME_LEN + d;
+                    }
+                    // The resolution and encoding set the width of the timestamp
+                    let mut resolution = TimeResolution::default();
+                    if has(FLAG_RESOLUTION) {
+                        let at = start
+                            + d
+                            + if has(FLAG_ENTROPY) {
+                                ENTROPY_LEN + d
+                            } else {
+                                0
+                            };
+                        resolution = body
+                            .get(at..at + RESOLUTION_LEN)
+                            .and_then(from_base36)
+                            .and_then(TimeResolution::from_code)
+                            .ok_or(PuidError::Malformed)?;
+                        start = at + RESOLUTION_LEN;
+                    }
+                    if !has(FLAG_ISO_TIME) && !has(FLAG_NO_TIME) {
+                        len = len + width(resolution.max()) - TIME_LEN;
+                    }
+                }
+                let fields = body.get(start..start + len).ok_or(PuidError::Malformed)?;
+                (version, fields, encoding)
+            }
+            Some(c) if c.is_ascii_alphabetic() => (
+                None,
+                body.get(..LEGACY_TIME_LEN).ok_or(PuidError::Malformed)?,
+                None,
+            ),
+            _ => return Err(PuidError::Malformed),
+        };
+        if !fields
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == separator)
+        {
+            return Err(PuidError::Malformed);
+        }
+
+        let encoding = match encoding {
+            Some(encoding) => encoding,
+            None if fields.chars().any(|c| c.is_ascii_uppercase()) => Encoding::Base62,
+            None => Encoding::Base36,
+        };
+
+        Ok(FormatInfo {
+            version,
+            separator,
+            encoding,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    #[cfg(feature = "std")]
+    use crate::{puid::DEFAULT_ENTROPY, PuidBuilder};
+    #[cfg(feature = "std")]
+    use std::time::Duration;
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn detect_format_v1_test() {
+        let id = Puid::builder().prefix("foo").unwrap().build().unwrap();
+        let format = Puid::detect_format(&id).unwrap();
+        assert_eq!(
+            format,
+            FormatInfo {
+                version: Some(VERSION),
+                separator: '_',
+                encoding: Encoding::Base36,
+            }
+        );
+
+        let id = Puid::builder()
+            .prefix("foo")
+            .unwrap()
+            .separator('-')
+            .build()
+            .unwrap();
+        assert_eq!(Puid::detect_format(&id).unwrap().separator, '-');
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn detect_format_legacy_test() {
+        let id = crate::puid("foo", 12);
+        let format = Puid::detect_format(&id).unwrap();
+        assert_eq!(
+            format,
+            FormatInfo {
+                version: None,
+                separator: '_',
+                encoding: Encoding::Base36,
+            }
+        );
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn fields_split_test() {
+        let fields = Fields::split("foo_10mv7m8kkc00000000000aj8OE3q1tEDrfSC").unwrap();
+        assert_eq!(fields.process_id, Some("0000aj8"));
+
+        assert!(Fields::split("foo_mv7m8kkc0aj8OE3q1tEDrfSC").is_err());
+        assert!(Fields::split("foo_10mv7m8kkc000000000").is_err());
+        assert!(Fields::split("foo_10MV7M8KKC000000aj8OE3q1tEDrfSC").is_err());
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn length_header_test() {
+        let id = Puid::builder()
+            .prefix("foo")
+            .unwrap()
+            .length_header(true)
+            .build()
+            .unwrap();
+        assert!(id.starts_with("foo_201"));
+        assert!(id.ends_with('3'));
+        assert_eq!(prefix_len(&id), Some(3));
+
+        let plain = Puid::builder().prefix("foo").unwrap().build().unwrap();
+        let fields = Fields::split(&id).unwrap();
+        assert_eq!(fields.process_id, Fields::split(&plain).unwrap().process_id);
+        assert_eq!(
+            Puid::detect_format(&id).unwrap().version,
+            Some(EXTENDED_VERSION)
+        );
+
+        // A header not matching the prefix is rejected
+        let tampered = format!("{}4", &id[..id.len() - 1]);
+        assert!(Fields::split(&tampered).is_err());
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn thread_id_test() {
+        let id = Puid::builder()
+            .prefix("foo")
+            .unwrap()
+            .include_thread_id(true)
+            .build()
+            .unwrap();
+        assert!(id.starts_with("foo_202"));
+
+        let plain = Puid::builder().prefix("foo").unwrap().build().unwrap();
+        let fields = Fields::split(&id).unwrap();
+        assert_eq!(fields.process_id, Fields::split(&plain).unwrap().process_id);
+    }
+
+    #[test]
+    fn prefix_len_test() {
+        assert_eq!(
+            prefix_len("foo_10mv7m8kkc00000000000aj8OE3q1tEDrfSC"),
+            Some(3)
+        );
+        // A last character pointing elsewhere falls back to scanning
+        assert_eq!(
+            prefix_len("foo_10mv7m8kkc00000000000aj8OE3q1tEDrfS5"),
+            Some(3)
+        );
+        assert_eq!(prefix_len("foo"), None);
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn prefix_of_test() {
+        let id = Puid::builder().prefix("foo").unwrap().build().unwrap();
+        let prefix = Puid::prefix_of(&id).unwrap();
+        assert_eq!(prefix, "foo");
+        assert_eq!(prefix.as_ptr(), id.as_ptr());
+
+        let id = Puid::builder()
+            .prefix("bar")
+            .unwrap()
+            .length_header(true)
+            .build()
+            .unwrap();
+        assert_eq!(Puid::prefix_of(&id).unwrap(), "bar");
+
+        assert!(matches!(Puid::prefix_of("foo"), Err(PuidError::Malformed)));
+        assert!(matches!(Puid::prefix_of("_foo"), Err(PuidError::Malformed)));
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn strip_prefix_test() {
+        let id = Puid::builder().prefix("foo").unwrap().build().unwrap();
+        let body = Puid::strip_prefix(&id, "foo").unwrap();
+        assert_eq!(body, &id["foo_".len()..]);
+
+        assert!(matches!(
+            Puid::strip_prefix(&id, "bar"),
+            Err(PuidError::PrefixMismatch)
+        ));
+        assert!(matches!(
+            Puid::strip_prefix(&id, "fo"),
+            Err(PuidError::PrefixMismatch)
+        ));
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn shard_test() {
+        let id = Puid::builder().prefix("foo").unwrap().build().unwrap();
+        assert_eq!(Puid::shard(&id, 16).unwrap(), Puid::shard(&id, 16).unwrap());
+        assert!(matches!(
+            Puid::shard(&id, 0),
+            Err(PuidError::InvalidShardCount)
+        ));
+
+        let mut counts = [0u32; 8];
+        for _ in 0..8000 {
+            let id = Puid::builder().prefix("foo").unwrap().build().unwrap();
+            counts[Puid::shard(&id, 8).unwrap() as usize] += 1;
+        }
+        assert!(counts.iter().all(|&count| (800..1200).contains(&count)));
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn tag_of_test() {
+        let id = Puid::builder()
+            .prefix("foo")
+            .unwrap()
+            .tag(42_000)
+            .build()
+            .unwrap();
+        assert!(id.starts_with("foo_204"));
+        assert_eq!(Puid::tag_of(&id).unwrap(), 42_000);
+
+        let id = Puid::builder()
+            .prefix("foo")
+            .unwrap()
+            .include_thread_id(true)
+            .tag(u16::MAX)
+            .build()
+            .unwrap();
+        assert_eq!(Puid::tag_of(&id).unwrap(), u16::MAX);
+
+        let id = Puid::builder().prefix("foo").unwrap().build().unwrap();
+        assert!(matches!(Puid::tag_of(&id), Err(PuidError::Malformed)));
+        assert!(matches!(Puid::tag_of("foo"), Err(PuidError::Malformed)));
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn timestamp_of_test() {
+        let builder = Puid::builder().prefix("foo").unwrap();
+        let id = builder.build_with_time(1651312057000).unwrap();
+        assert_eq!(Puid::timestamp_of(&id).unwrap(), 1651312057000);
+
+        let builder = Puid::builder().prefix("foo").unwrap().iso_timestamp(true);
+        let id = builder.build_with_time(1651312057000).unwrap();
+        assert_eq!(Puid::timestamp_of(&id).unwrap(), 1651312057000);
+
+        assert!(matches!(
+            Puid::timestamp_of("foo_bar"),
+            Err(PuidError::Malformed)
+        ));
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn is_expired_test() {
+        let builder = Puid::builder().prefix("foo").unwrap();
+        let id = builder
+            .expires_in(Duration::from_secs(3600))
+            .build()
+            .unwrap();
+        assert!(!Puid::is_expired(&id).unwrap());
+
+        let id = Puid::builder()
+            .prefix("foo")
+            .unwrap()
+            .expires_in(Duration::from_secs(3600))
+            .build_with_time(1651312057000)
+            .unwrap();
+        assert!(Puid::is_expired(&id).unwrap());
+        let random = id.len() - usize::from(DEFAULT_ENTROPY);
+        assert_eq!(&id[random - 9..random], "0l2lqpfl4");
+
+        let id = Puid::builder().prefix("foo").unwrap().build().unwrap();
+        assert!(matches!(Puid::is_expired(&id), Err(PuidError::Malformed)));
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn locale_of_test() {
+        let builder = || Puid::builder().prefix("post").unwrap();
+        let id = builder().locale("en-US").unwrap().build().unwrap();
+        assert!(id.starts_with("post_enus_1"));
+        assert_eq!(Puid::locale_of(&id).unwrap(), "en-us");
+        assert_eq!(
+            id.len(),
+            "post_enus_".len() + 24 + usize::from(DEFAULT_ENTROPY)
+        );
+        assert_eq!(Puid::parse(&id).unwrap().prefix, "post");
+
+        for (tag, locale) in [("pt_br", "pt-br"), ("fil-PH", "fil-ph"), ("de", "de")] {
+            let id = builder().locale(tag).unwrap().build().unwrap();
+            assert_eq!(Puid::locale_of(&id).unwrap(), locale);
+            let other = PuidBuilder::infer_from(&id).unwrap().build().unwrap();
+            assert_eq!(Puid::locale_of(&other).unwrap(), locale);
+        }
+
+        for tag in ["", "e", "english", "en-", "en-USA", "e1", "en-us-x"] {
+            assert!(matches!(
+                builder().locale(tag),
+                Err(PuidError::InvalidLocale)
+            ));
+        }
+        let id = builder().build().unwrap();
+        assert!(matches!(Puid::locale_of(&id), Err(PuidError::Malformed)));
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn validate_timestamp_test() {
+        // 2020-01-01 to 2100-01-01
+        let (min, max) = (1_577_836_800_000, 4_102_444_800_000);
+        let id = Puid::builder().prefix("foo").unwrap().build().unwrap();
+        assert!(Puid::validate_timestamp(&id, min, max).unwrap());
+
+        let builder = || Puid::builder().prefix("foo").unwrap();
+        let id = builder().build_with_time(1_000).unwrap();
+        assert!(!Puid::validate_timestamp(&id, min, max).unwrap());
+        let id = builder().build_with_time(253_402_300_799_000).unwrap();
+        assert!(!Puid::validate_timestamp(&id, min, max).unwrap());
+        let id = builder().build_with_time(min).unwrap();
+        assert!(Puid::validate_timestamp(&id, min, max).unwrap());
+
+        assert!(matches!(
+            Puid::validate_timestamp("foo", min, max),
+            Err(PuidError::Malformed)
+        ));
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn clock_skew_test() {
+        let id = Puid::builder().prefix("foo").unwrap().build().unwrap();
+        assert!((0..1000).contains(&Puid::clock_skew(&id).unwrap()));
+
+        let future = time() + 60_000;
+        let id = Puid::builder()
+            .prefix("foo")
+            .unwrap()
+            .build_with_time(future)
+            .unwrap();
+        assert!((-60_000..-59_000).contains(&Puid::clock_skew(&id).unwrap()));
+
+        assert!(matches!(Puid::clock_skew("foo"), Err(PuidError::Malformed)));
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn decode_body_test() {
+        let encode = |bytes: &[u8]| -> String {
+            let alphabet = BASE_64_URL.as_bytes();
+            bytes
+                .chunks(3)
+                .flat_map(|chunk| {
+                    let bits = u32::from_be_bytes([0, chunk[0], chunk[1], chunk[2]]);
+                    (0..4)
+                        .rev()
+                        .map(move |i| char::from(alphabet[(bits >> (6 * i)) as usize & 63]))
+                })
+                .collect()
+        };
+
+        for entropy in [10, 11, 12, 13] {
+            let id = Puid::builder()
+                .prefix("foo")
+                .unwrap()
+                .entropy(entropy)
+                .readable()
+                .decodable(true)
+                .build()
+                .unwrap();
+            let body = &id["foo_".len()..];
+            let bytes = Puid::decode_body(&id).unwrap();
+            assert_eq!(encode(&bytes), body);
+        }
+
+        let id = Puid::builder()
+            .prefix("foo")
+            .unwrap()
+            .entropy(13)
+            .build()
+            .unwrap();
+        assert!(matches!(Puid::decode_body(&id), Err(PuidError::Malformed)));
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn short_test() {
+        let id = Puid::builder().prefix("foo").unwrap().build().unwrap();
+        let short = Puid::short(&id, 7).unwrap();
+        assert!(short.starts_with("foo_"));
+        assert_eq!(short.len(), "foo_".len() + 7);
+        assert!(id.starts_with(&short));
+
+        assert_eq!(Puid::short("foo_1abc", 7).unwrap(), "foo_1abc");
+        assert!(matches!(Puid::short("foo", 7), Err(PuidError::Malformed)));
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn mask_test() {
+        let id = Puid::builder().prefix("foo").unwrap().build().unwrap();
+        let masked = Puid::mask(&id, true, 4);
+        assert_eq!(masked.len(), id.len());
+        assert!(masked.starts_with("foo_*"));
+        assert_eq!(&masked[masked.len() - 4..], &id[id.len() - 4..]);
+        assert!(masked[4..masked.len() - 4].chars().all(|c| c == '*'));
+
+        assert_eq!(Puid::mask("foo_1abc", false, 2), "******bc");
+        assert_eq!(Puid::mask("foo_1abc", true, 8), "foo_1abc");
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn potential_duplicate_test() {
+        let build = |prefix, time| {
+            Puid::builder()
+                .prefix(prefix)
+                .unwrap()
+                .counter_start(5)
+                .build_with_time(time)
+                .unwrap()
+        };
+        let a = build("foo", 1651312057000);
+        assert!(Puid::potential_duplicate(&a, &a, 0).unwrap());
+        let b = build("foo", 1651312057010);
+        assert!(Puid::potential_duplicate(&a, &b, 10).unwrap());
+        assert!(Puid::potential_duplicate(&b, &a, 10).unwrap());
+        assert!(!Puid::potential_duplicate(&a, &b, 9).unwrap());
+
+        let c = build("foo", 1651312060000);
+        assert!(!Puid::potential_duplicate(&a, &c, 1000).unwrap());
+        let d = build("bar", 1651312057000);
+        assert!(!Puid::potential_duplicate(&a, &d, 1000).unwrap());
+        let e = Puid::builder()
+            .prefix("foo")
+            .unwrap()
+            .counter_start(6)
+            .build_with_time(1651312057000)
+            .unwrap();
+        assert!(!Puid::potential_duplicate(&a, &e, 1000).unwrap());
+
+        assert!(Puid::potential_duplicate(&a, "foo", 1000).is_err());
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn same_origin_test() {
+        let a = Puid::builder().prefix("foo").unwrap().build().unwrap();
+        let b = Puid::builder().prefix("bar").unwrap().build().unwrap();
+        assert!(Puid::same_origin(&a, &b).unwrap());
+
+        // Same ID with another process ID field
+        let pid = Fields::split(&a).unwrap().process_id.unwrap();
+        let other = if pid == "0000001" {
+            "0000002"
+        } else {
+            "0000001"
+        };
+        let c = a.replacen(pid, other, 1);
+        assert!(!Puid::same_origin(&a, &c).unwrap());
+
+        assert!(Puid::same_origin(&a, "foo").is_err());
+        let d = Puid::builder()
+            .prefix("foo")
+            .unwrap()
+            .include_pid(false)
+            .build()
+            .unwrap();
+        assert!(Puid::same_origin(&a, &d).is_err());
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn validate_id_test() {
+        let id = Puid::builder().prefix("foo").unwrap().build().unwrap();
+        assert!(Puid::is_valid(&id));
+        assert!(Puid::is_valid("foo_10mv7m8kkc00000000000aj8OE3q1tEDrfSC"));
+
+        assert!(!Puid::is_valid(""));
+        assert!(!Puid::is_valid("foo"));
+        assert!(!Puid::is_valid("_10mv7m8kkc00000000000aj8OE3q1tEDrfSC"));
+        assert!(!Puid::is_valid("foo_10mv7m8kkc0000000000!aj8OE3q1tEDrfSC"));
+        assert!(matches!(
+            Puid::validate_id("foo_10mv7m8Kkc00000000000aj8OE3q1tEDrfSC"),
+            Err(PuidError::Malformed)
+        ));
+        // The counter is a u32
+        assert!(matches!(
+            Puid::validate_id("foo_10mv7m8kkczzzzzzz0000000aj8OE3q1tEDrfSC"),
+            Err(PuidError::Malformed)
+        ));
+        assert!(matches!(
+            Puid::validate_id("foooooooooooooooooooooooooooooooooo_10mv7m8kkc00000000000aj8"),
+            Err(PuidError::InvalidPrefix)
+        ));
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn canonicalize_test() {
+        let id = "foo_10mv7m8kkc00000000000aj8OE3q1tEDrfSC";
+        let variant = "FOO-10MV7M8KKC00000000000AJ8OE3q1tEDrfSC";
+        assert_eq!(Puid::canonicalize(variant).unwrap(), id);
+        assert_eq!(Puid::canonicalize(id).unwrap(), id);
+        // The random sequence is case sensitive
+        let other = "foo_10mv7m8kkc00000000000aj8oe3q1tedrfsc";
+        assert_ne!(Puid::canonicalize(other).unwrap(), id);
+
+        let id = Puid::builder()
+            .prefix("foo")
+            .unwrap()
+            .crockford_base32(true)
+            .build()
+            .unwrap();
+        let (head, random) = id.split_at(id.len() - usize::from(DEFAULT_ENTROPY));
+        let variant = [&head.to_ascii_lowercase(), random].concat();
+        assert_eq!(Puid::canonicalize(&variant).unwrap(), id);
+
+        let id = Puid::builder()
+            .prefix("foo")
+            .unwrap()
+            .separator('-')
+            .delimited_fields(true)
+            .tag(7)
+            .build()
+            .unwrap();
+        let canonical = Puid::canonicalize(&id).unwrap();
+        assert_eq!(canonical, id.replace('-', "_"));
+        assert_eq!(Puid::tag_of(&canonical).unwrap(), 7);
+
+        assert!(Puid::canonicalize("FOO").is_err());
+        assert!(Puid::canonicalize("foo_l2lok9t4").is_err());
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn validate_id_with_policy_test() {
+        let id = Puid::builder().prefix("Foo").unwrap().build().unwrap();
+        assert!(Puid::validate_id_with_policy(&id, false, &["bar", "Foo"]).is_ok());
+        assert!(Puid::validate_id_with_policy(&id, true, &["bar", "foo"]).is_ok());
+        assert!(matches!(
+            Puid::validate_id_with_policy(&id, false, &["bar", "foo"]),
+            Err(PuidError::PrefixMismatch)
+        ));
+        assert!(matches!(
+            Puid::validate_id_with_policy(&id, true, &["bar"]),
+            Err(PuidError::PrefixMismatch)
+        ));
+        assert!(matches!(
+            Puid::validate_id_with_policy("Foo_", true, &["foo"]),
+            Err(PuidError::Malformed)
+        ));
+    }
+
+    #[test]
+    fn detect_format_malformed_test() {
+        assert!(matches!(
+            Puid::detect_format("foo"),
+            Err(PuidError::Malformed)
+        ));
+        assert!(matches!(
+            Puid::detect_format("_10mv7m8kkc"),
+            Err(PuidError::Malformed)
+        ));
+        assert!(matches!(
+            Puid::detect_format("foo_1abc"),
+            Err(PuidError::Malformed)
+        ));
+    }
+}
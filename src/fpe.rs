@@ -0,0 +1,164 @@
+use crate::{
+    errors::{PuidError, PuidResult},
+    format::Fields,
+    puid::{fnv1a_64, FLAG_ISO_TIME, FLAG_NO_TIME},
+    Puid,
+};
+
+// Rounds of the Feistel network
+const ROUNDS: u64 = 8;
+
+/// Derives the 64-bit key of the cipher from the key bytes.
+pub(crate) fn derive_key(key: &[u8]) -> u64 {
+    fnv1a_64(u64::MAX, key)
+}
+
+/// Encrypts a value below `domain` into another value below `domain`.
+pub(crate) fn encrypt(key: u64, v: u128, domain: u128) -> u128 {
+    walk(key, v, domain, true)
+}
+
+/// Recovers a value encrypted with `encrypt`.
+pub(crate) fn decrypt(key: u64, v: u128, domain: u128) -> u128 {
+    walk(key, v, domain, false)
+}
+
+/// Runs a balanced Feistel network over the bits covering `domain`, walking
+/// the cycle until the result falls back below `domain`.
+fn walk(key: u64, mut v: u128, domain: u128, forward: bool) -> u128 {
+    let bits = (u128::BITS - domain.saturating_sub(1).leading_zeros()).max(2);
+    let half = bits.div_ceil(2);
+    let mask = (1 << half) - 1;
+    let f = |round: u64, half: u128| {
+        let half = u64::try_from(half).unwrap();
+        u128::from(fnv1a_64(key ^ round, &half.to_le_bytes())) & mask
+    };
+    loop {
+        let (mut left, mut right) = (v >> half, v & mask);
+        for round in 0..ROUNDS {
+            if forward {
+                (left, right) = (right, left ^ f(round, right));
+            } else {
+                (left, right) = (right ^ f(ROUNDS - 1 - round, left), left);
+            }
+        }
+        v = left << half | right;
+        if v < domain {
+            return v;
+        }
+    }
+}
+
+impl Puid {
+    /// Recovers the timestamp of an ID built with
+    /// `PuidBuilder::encrypted_sortable`, in milliseconds since the UNIX
+    /// epoch, e.g. to sort IDs by creation time.
+    ///
+    /// The IDs carry no mark of the encryption, so the key must be the one
+    /// they were built with or the time is meaningless. Returns
+    /// `PuidError::Malformed` if the ID does not match the layout or carries
+    /// no numeric timestamp.
+    pub fn decrypt_timestamp(id: &str, key: &[u8]) -> PuidResult<u128> {
+        let id = Puid::unscramble(id)?;
+        let fields = Fields::split(&id)?;
+        // Only numeric timestamps are encrypted
+        if fields.flags & (FLAG_ISO_TIME | FLAG_NO_TIME) != 0 {
+            return Err(PuidError::Malformed);
+        }
+        let base: u128 = if fields.crockford { 32 } else { 36 };
+        let domain = base.pow(u32::try_from(fields.timestamp.len()).unwrap());
+        let ticks = fields
+            .decode(fields.timestamp)
+            .ok_or(PuidError::Malformed)?;
+        Ok(fields
+            .resolution
+            .millis(decrypt(derive_key(key), ticks, domain)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn fpe_test() {
+        let domain = 36u128.pow(9);
+        for v in [0, 1, 1_651_312_057_000, domain - 1] {
+            let encrypted = encrypt(42, v, domain);
+            assert!(encrypted < domain);
+            assert_eq!(decrypt(42, encrypted, domain), v);
+            assert_ne!(encrypt(43, v, domain), encrypted);
+        }
+        for v in 0..10 {
+            assert_eq!(decrypt(7, encrypt(7, v, 10), 10), v);
+        }
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn encrypted_sortable_test() {
+        let key = b"secret";
+        let start = 1_651_312_057_000;
+        let ids: Vec<_> = (0..20)
+            .map(|i| {
+                Puid::builder()
+                    .prefix("foo")
+                    .unwrap()
+                    .clock(move || start + i * 1000)
+                    .encrypted_sortable(key)
+                    .build()
+                    .unwrap()
+            })
+            .collect();
+        for (i, id) in ids.iter().enumerate() {
+            assert_eq!(
+                Puid::decrypt_timestamp(id, key).unwrap(),
+                start + u128::try_from(i).unwrap() * 1000
+            );
+            assert_ne!(Puid::timestamp_of(id).unwrap(), start);
+        }
+
+        // Holders of the key recover the order, lexical order is lost
+        let mut sorted = ids.clone();
+        sorted.sort_by_key(|id| Puid::decrypt_timestamp(id, key).unwrap());
+        assert_eq!(sorted, ids);
+        let mut lexical = ids.clone();
+        lexical.sort();
+        assert_ne!(lexical, ids);
+
+        let id = Puid::builder()
+            .prefix("foo")
+            .unwrap()
+            .clock(move || start)
+            .crockford_base32(true)
+            .encrypted_sortable(key)
+            .build()
+            .unwrap();
+        assert_eq!(Puid::decrypt_timestamp(&id, key).unwrap(), start);
+        let id = Puid::builder()
+            .prefix("foo")
+            .unwrap()
+            .clock(move || start)
+            .scramble(true)
+            .encrypted_sortable(key)
+            .build()
+            .unwrap();
+        assert_eq!(Puid::decrypt_timestamp(&id, key).unwrap(), start);
+
+        let builder = || {
+            Puid::builder()
+                .prefix("foo")
+                .unwrap()
+                .encrypted_sortable(key)
+        };
+        assert!(matches!(
+            builder().sortable(true).build(),
+            Err(PuidError::Unsortable)
+        ));
+        assert!(matches!(
+            builder().iso_timestamp(true).build(),
+            Err(PuidError::ConflictingOptions)
+        ));
+        assert!(Puid::decrypt_timestamp("foo_l2lok9t4", key).is_err());
+    }
+}
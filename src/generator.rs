@@ -0,0 +1,54 @@
+use crate::{errors::PuidResult, PuidBuilder};
+use std::{
+    sync::mpsc::{self, Receiver},
+    thread::{self, JoinHandle},
+};
+
+impl PuidBuilder<'static> {
+    /// Spawns a thread pre-generating IDs into a channel bounded to `buffer`
+    /// IDs, so consumers `recv()` them without waiting for generation.
+    ///
+    /// The thread blocks while the channel is full and stops once the
+    /// receiver is dropped, so joining the handle after dropping it returns
+    /// `Ok(())`. If generating an ID fails, the thread stops, the channel
+    /// disconnects and the handle returns the error. A `buffer` of 0 hands
+    /// over each ID as it is received. The configuration is validated first,
+    /// like with `build()`.
+    pub fn spawn_generator(self, buffer: usize) -> (JoinHandle<PuidResult<()>>, Receiver<String>) {
+        let (sender, receiver) = mpsc::sync_channel(buffer);
+        let handle = thread::spawn(move || {
+            self.validate()?;
+            loop {
+                if sender.send(self.generate()?).is_err() {
+                    return Ok(());
+                }
+            }
+        });
+        (handle, receiver)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{errors::PuidError, Puid};
+    use std::collections::HashSet;
+
+    #[test]
+    fn spawn_generator_test() {
+        let builder = Puid::builder().prefix("foo").unwrap();
+        let (handle, ids) = builder.spawn_generator(8);
+        let received: HashSet<_> = ids.iter().take(100).collect();
+        assert_eq!(received.len(), 100);
+        assert!(received.iter().all(|id| id.starts_with("foo_1")));
+
+        drop(ids);
+        assert!(handle.join().unwrap().is_ok());
+
+        let (handle, ids) = Puid::builder().spawn_generator(0);
+        assert!(ids.recv().is_err());
+        assert!(matches!(
+            handle.join().unwrap(),
+            Err(PuidError::InvalidPrefix)
+        ));
+    }
+}
@@ -0,0 +1,166 @@
+use crate::{
+    errors::{PuidError, PuidResult},
+    Puid,
+};
+use alloc::string::{String, ToString};
+use core::{fmt, str::FromStr};
+
+/// A typed ID, keeping a Puid apart from any other string.
+#[derive(Clone, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct PuidId {
+    id: String,
+    // Length of the prefix at the start of `id`
+    prefix_len: usize,
+    // Whether the ID was written by the deprecated `puid()`
+    legacy: bool,
+}
+
+impl PuidId {
+    /// Wraps an ID produced by the deprecated `puid()` function or `puid!`
+    /// macro, without a version marker, so old and new IDs can coexist in one
+    /// typed collection.
+    ///
+    /// Only the prefix is recovered from the legacy layout. Returns
+    /// `PuidError::Malformed` if the string is not a legacy ID.
+    pub fn from_legacy(s: &str) -> PuidResult<PuidId> {
+        if Puid::detect_format(s)?.version.is_some() {
+            return Err(PuidError::Malformed);
+        }
+        let parts = Puid::parse_lenient(s)?;
+        Ok(PuidId {
+            id: s.to_string(),
+            prefix_len: parts.prefix.len(),
+            legacy: true,
+        })
+    }
+
+    /// Returns the ID as a string slice.
+    #[must_use]
+    pub fn as_str(&self) -> &str {
+        &self.id
+    }
+
+    /// Returns the prefix of the ID.
+    #[must_use]
+    pub fn prefix(&self) -> &str {
+        &self.id[..self.prefix_len]
+    }
+
+    /// Returns whether the ID has the legacy layout of the deprecated `puid()`.
+    #[must_use]
+    pub fn is_legacy(&self) -> bool {
+        self.legacy
+    }
+}
+
+impl fmt::Display for PuidId {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.id)
+    }
+}
+
+impl FromStr for PuidId {
+    type Err = PuidError;
+
+    /// Parses a versioned ID, validated like `Puid::parse`.
+    fn from_str(s: &str) -> PuidResult<Self> {
+        let parts = Puid::parse(s)?;
+        Ok(PuidId {
+            id: s.to_string(),
+            prefix_len: parts.prefix.len(),
+            legacy: false,
+        })
+    }
+}
+
+impl AsRef<str> for PuidId {
+    fn as_ref(&self) -> &str {
+        &self.id
+    }
+}
+
+#[cfg(feature = "serde")]
+impl serde::Serialize for PuidId {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(&self.id)
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de> serde::Deserialize<'de> for PuidId {
+    /// Deserializes a versioned ID, or a legacy one so serialized legacy IDs
+    /// round-trip, failing on any other string.
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let s = <alloc::borrow::Cow<'de, str>>::deserialize(deserializer)?;
+        s.parse()
+            .or_else(|_| PuidId::from_legacy(&s))
+            .map_err(serde::de::Error::custom)
+    }
+}
+
+#[cfg(all(test, feature = "std"))]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn puid_id_from_legacy_test() {
+        #[allow(deprecated)]
+        let legacy = crate::puid("foo", 12);
+        let id = PuidId::from_legacy(&legacy).unwrap();
+        assert_eq!(id.prefix(), "foo");
+        assert_eq!(id.as_str(), legacy);
+        assert!(id.is_legacy());
+
+        let current = Puid::builder().prefix("foo").unwrap().build().unwrap();
+        assert!(matches!(
+            PuidId::from_legacy(&current),
+            Err(PuidError::Malformed)
+        ));
+        assert!(PuidId::from_legacy("foo").is_err());
+    }
+
+    #[test]
+    fn puid_id_from_str_test() {
+        let id = Puid::builder()
+            .prefix("foo")
+            .unwrap()
+            .build_typed()
+            .unwrap();
+        assert_eq!(id.prefix(), "foo");
+        assert!(!id.is_legacy());
+        assert_eq!(id.to_string().parse::<PuidId>().unwrap(), id);
+        assert_eq!(id.as_ref(), id.as_str());
+
+        let mut ids = std::collections::HashMap::new();
+        ids.insert(id.clone(), 1);
+        assert_eq!(ids.get(&id), Some(&1));
+
+        assert!(matches!(
+            "foo_bar".parse::<PuidId>(),
+            Err(PuidError::Malformed)
+        ));
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn puid_id_serde_test() {
+        let id = Puid::builder()
+            .prefix("foo")
+            .unwrap()
+            .build_typed()
+            .unwrap();
+        let json = serde_json::to_string(&id).unwrap();
+        assert_eq!(json, format!("\"{id}\""));
+        assert_eq!(serde_json::from_str::<PuidId>(&json).unwrap(), id);
+
+        #[allow(deprecated)]
+        let legacy = PuidId::from_legacy(&crate::puid("foo", 12)).unwrap();
+        let json = serde_json::to_string(&legacy).unwrap();
+        assert_eq!(serde_json::from_str::<PuidId>(&json).unwrap(), legacy);
+
+        let err = serde_json::from_str::<PuidId>("\"foo_bar\"").unwrap_err();
+        assert!(err
+            .to_string()
+            .contains("ID does not match the expected layout"));
+    }
+}
@@ -0,0 +1,110 @@
+use alloc::{format, string::String};
+
+/// Characters of an ISO-8601 timestamp, `YYYYMMDDTHHMMSSmmm`.
+pub(crate) const ISO_TIME_LEN: usize = 18;
+
+const MS_PER_DAY: u128 = 86_400_000;
+
+/// Formats milliseconds since the UNIX epoch as a basic ISO-8601 timestamp
+/// in UTC with milliseconds, e.g. `20240612T101500000`.
+pub(crate) fn format(ms: u128) -> String {
+    let days = i64::try_from(ms / MS_PER_DAY).unwrap_or(i64::MAX);
+    let (year, month, day) = civil_from_days(days);
+    let ms = ms % MS_PER_DAY;
+    format!(
+        "{year:04}{month:02}{day:02}T{:02}{:02}{:02}{:03}",
+        ms / 3_600_000,
+        ms / 60_000 % 60,
+        ms / 1000 % 60,
+        ms % 1000
+    )
+}
+
+/// Parses a timestamp written by `format` back into milliseconds since the
+/// UNIX epoch, `None` if it is not a valid one.
+pub(crate) fn parse(s: &str) -> Option<u128> {
+    if s.len() != ISO_TIME_LEN || s.as_bytes()[8] != b'T' {
+        return None;
+    }
+    let field = |range: core::ops::Range<usize>| {
+        let digits = s.get(range)?;
+        if digits.bytes().all(|b| b.is_ascii_digit()) {
+            digits.parse::<u32>().ok()
+        } else {
+            None
+        }
+    };
+    let (year, month, day) = (field(0..4)?, field(4..6)?, field(6..8)?);
+    let (hour, minute, second, ms) = (
+        field(9..11)?,
+        field(11..13)?,
+        field(13..15)?,
+        field(15..18)?,
+    );
+    if !(1..=12).contains(&month) || hour > 23 || minute > 59 || second > 59 {
+        return None;
+    }
+
+    let days = days_from_civil(i64::from(year), month, day);
+    // Rejects days past the end of the month, which roll over
+    if civil_from_days(days) != (i64::from(year), month, day) {
+        return None;
+    }
+    let days = u128::try_from(days).ok()?;
+    Some(
+        days * MS_PER_DAY
+            + u128::from(hour) * 3_600_000
+            + u128::from(minute) * 60_000
+            + u128::from(second) * 1000
+            + u128::from(ms),
+    )
+}
+
+/// Converts days since the UNIX epoch into a proleptic Gregorian date.
+fn civil_from_days(days: i64) -> (i64, u32, u32) {
+    let z = days + 719_468;
+    let era = z.div_euclid(146_097);
+    let doe = z.rem_euclid(146_097);
+    let yoe = (doe - doe / 1460 + doe / 36524 - doe / 146_096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = u32::try_from(doy - (153 * mp + 2) / 5 + 1).unwrap();
+    let month = u32::try_from(if mp < 10 { mp + 3 } else { mp - 9 }).unwrap();
+    let year = yoe + era * 400 + i64::from(month <= 2);
+    (year, month, day)
+}
+
+/// Converts a proleptic Gregorian date into days since the UNIX epoch.
+fn days_from_civil(year: i64, month: u32, day: u32) -> i64 {
+    let year = if month <= 2 { year - 1 } else { year };
+    let era = year.div_euclid(400);
+    let yoe = year.rem_euclid(400);
+    let month = i64::from(month);
+    let mp = if month > 2 { month - 3 } else { month + 9 };
+    let doy = (153 * mp + 2) / 5 + i64::from(day) - 1;
+    let doe = yoe * 365 + yoe / 4 - yoe / 100 + doy;
+    era * 146_097 + doe - 719_468
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn iso_format_test() {
+        assert_eq!(format(0), "19700101T000000000");
+        assert_eq!(format(1_718_187_300_123), "20240612T101500123");
+        assert_eq!(format(951_782_400_000), "20000229T000000000");
+    }
+
+    #[test]
+    fn iso_parse_test() {
+        for ms in [0, 951_782_400_000, 1_718_187_300_123, 101_559_956_668_415] {
+            assert_eq!(parse(&format(ms)), Some(ms));
+        }
+        assert_eq!(parse("20240230T000000000"), None);
+        assert_eq!(parse("20241301T000000000"), None);
+        assert_eq!(parse("20240612t101500123"), None);
+        assert_eq!(parse("20240612T1015001"), None);
+    }
+}
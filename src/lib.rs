@@ -6,9 +6,11 @@
 //!
 //! - **Prefix**: A user-defined alphanumeric prefix.
 //! - **Separator**: An underscore (`_`) character.
-//! - **Timestamp**: The current timestamp, encoded in Base-36.
-//! - **Counter**: An atomic `u8` counter to ensure unique IDs in rapid succession.
-//! - **Process ID**: The OS-assigned process identifier, encoded in Base-36.
+//! - **Version**: A single digit identifying the layout of the fields that follow.
+//! - **Timestamp**: The current timestamp, encoded in Base-36 and padded to 9 characters.
+//! - **Counter**: An atomic `u32` counter to ensure unique IDs in rapid succession, encoded in Base-36 and padded to 7 characters.
+//!   IDs timestamped by the system clock never repeat a timestamp and counter on one host: when the counter wraps, later IDs move to a later tick.
+//! - **Process ID**: The OS-assigned process identifier, encoded in Base-36 and padded to 7 characters.
 //! - **Random Sequence**: A customizable sequence of random alphanumeric characters, providing additional entropy.
 //!
 //! # Examples
@@ -23,7 +25,7 @@
 //!     .unwrap()
 //!     .build()
 //!     .unwrap();
-//! // Example output: "foo_l2ok01bl0yq2i2ElC7zWaCR8"
+//! // Example output: "foo_10mv7m8kkc00000000000aj8OE3q1tEDrfSC"
 //! ```
 //!
 //! ## Using a custom random sequence length
@@ -37,21 +39,124 @@
 //!     .entropy(24)
 //!     .build()
 //!     .unwrap();
-//! // Example output: "bar_l2ok1yvk1z4aOz1P7kecCTaqUGq1wgKfHGZC"
+//! // Example output: "bar_10mv7m8kkc00000010000aj8EpBvUBU1dj8XeL3LyEO7BsGe"
 //! ```
 //!
 //! # Error Handling
 //!
-//! `Puid::builder` returns a `Result` indicating potential errors in configuration, such as an invalid prefix. The prefix must be alphanumeric and 1-8 characters long.
+//! `Puid::builder` returns a `Result` indicating potential errors in configuration, such as an invalid prefix. The prefix must be alphanumeric and 1-8 characters long, up to 32 with `max_prefix_len`.
 
 #![doc(html_root_url = "https://docs.rs/puid")]
+#![cfg_attr(not(feature = "std"), no_std)]
 #![warn(missing_docs)]
 #![allow(deprecated)]
 
+extern crate alloc;
+
 /// The Puid module.
 mod puid;
 
-pub use crate::puid::{puid, Puid};
+#[cfg(feature = "std")]
+pub use crate::puid::puid;
+pub use crate::puid::{
+    puid_v2, Case, CharSet, Flavor, Puid, PuidBuilder, PuidIntoIter, PuidIter, RetryStrategy,
+    TimeResolution, MAX_ENTROPY,
+};
+
+/// Clock sources of Puids.
+mod clock;
+
+#[cfg(feature = "std")]
+pub use crate::clock::SystemClock;
+pub use crate::clock::{Clock, VirtualClock};
+
+/// Format detection of Puids.
+mod format;
+
+pub use crate::format::{Encoding, FormatInfo};
+
+/// Parsing of Puids.
+mod parse;
+
+pub use crate::parse::PuidParts;
+
+/// Runtime-dispatched prefixes of Puids.
+mod prefix;
+
+pub use crate::prefix::DynPrefix;
+
+/// Typed Puids.
+mod id;
+
+pub use crate::id::PuidId;
+
+/// Prefix coding of Puids.
+mod codec;
+
+pub use crate::codec::PrefixCodec;
+
+/// ISO-8601 timestamps of Puids.
+mod iso;
+
+/// Scrambling of Puids.
+mod scramble;
+
+/// Timestamp encryption of Puids.
+mod fpe;
+
+/// Configuration linting of Puids.
+mod lint;
+
+pub use crate::lint::LintWarning;
+
+/// Configuration compatibility of Puids.
+mod compat;
+
+pub use crate::compat::Compatibility;
+
+/// Correlated pairs of Puids.
+mod correlate;
+
+/// Counter state persistence of Puids.
+mod state;
+
+pub use crate::state::PuidState;
+
+/// Buffer pooling of Puids.
+mod pool;
+
+pub use crate::pool::{PooledPuid, PuidPool};
+
+/// Background generation of Puids.
+#[cfg(feature = "std")]
+mod generator;
+
+/// Compile-time features of Puids.
+mod features;
+
+pub use crate::features::Features;
+
+/// HMAC signing of Puids.
+#[cfg(feature = "hmac")]
+mod sign;
+
+/// Generation metrics of Puids.
+#[cfg(feature = "metrics")]
+mod metrics;
+
+#[cfg(feature = "metrics")]
+pub use crate::metrics::Metrics;
+
+/// Secret generation with Puid's sampler.
+#[cfg(feature = "secure")]
+mod secret;
+
+/// Locking of Puids.
+mod sync;
+
+/// Allocation tracking for tests.
+#[cfg(all(test, feature = "std"))]
+mod tracking;
 
 /// The type error.
 pub mod errors;
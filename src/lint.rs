@@ -0,0 +1,78 @@
+use crate::{Puid, PuidBuilder};
+use alloc::vec::Vec;
+
+/// Entropy of the random sequence recommended to avoid collisions.
+const RECOMMENDED_ENTROPY_BITS: u32 = 64;
+
+/// A weakness found in a builder configuration.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum LintWarning {
+    /// The random sequence carries fewer bits than recommended.
+    LowEntropy {
+        /// The bits of entropy of the random sequence.
+        bits: u32,
+    },
+    /// The counter may wrap within a single timestamp under high rates,
+    /// leaving uniqueness to the random sequence alone.
+    CounterWrap,
+    /// The separator collides with the characters of the prefix or fields.
+    SeparatorCollision,
+}
+
+impl Puid {
+    /// Inspects a builder configuration and reports its weaknesses.
+    ///
+    /// An empty result means the configuration follows the recommendations.
+    #[must_use]
+    pub fn lint(builder: &PuidBuilder) -> Vec<LintWarning> {
+        let mut warnings = Vec::new();
+
+        let bits = builder.entropy_bits();
+        if bits < RECOMMENDED_ENTROPY_BITS {
+            warnings.push(LintWarning::LowEntropy { bits });
+        }
+        if builder.has_frozen_clock() {
+            warnings.push(LintWarning::CounterWrap);
+        }
+        if builder.separator_collides() {
+            warnings.push(LintWarning::SeparatorCollision);
+        }
+
+        warnings
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloc::vec;
+
+    #[test]
+    fn lint_strong_test() {
+        let builder = Puid::builder().prefix("foo").unwrap();
+        assert!(Puid::lint(&builder).is_empty());
+    }
+
+    #[test]
+    fn lint_weak_test() {
+        let builder = Puid::builder()
+            .prefix("foo")
+            .unwrap()
+            .entropy(4)
+            .separator('a');
+        assert_eq!(
+            Puid::lint(&builder),
+            vec![
+                LintWarning::LowEntropy { bits: 23 },
+                LintWarning::SeparatorCollision
+            ]
+        );
+    }
+
+    #[cfg(feature = "testing")]
+    #[test]
+    fn lint_frozen_clock_test() {
+        let builder = Puid::builder().prefix("foo").unwrap().deterministic(42);
+        assert_eq!(Puid::lint(&builder), vec![LintWarning::CounterWrap]);
+    }
+}
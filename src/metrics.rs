@@ -0,0 +1,61 @@
+use crate::Puid;
+use core::sync::atomic::{AtomicU32, AtomicU64, Ordering};
+
+// Process-wide counters updated on every generated ID
+static GENERATED: AtomicU64 = AtomicU64::new(0);
+static PEAK_COUNTER: AtomicU32 = AtomicU32::new(0);
+static COUNTER_WRAPS: AtomicU64 = AtomicU64::new(0);
+
+/// A snapshot of the process-wide generation metrics.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct Metrics {
+    /// The total of IDs generated.
+    pub generated: u64,
+    /// The highest counter value reached.
+    pub peak_counter: u32,
+    /// The number of times a counter wrapped around.
+    pub counter_wraps: u64,
+}
+
+impl Puid {
+    /// Returns a snapshot of the generation metrics of the process.
+    #[must_use]
+    pub fn metrics() -> Metrics {
+        Metrics {
+            generated: GENERATED.load(Ordering::Relaxed),
+            peak_counter: PEAK_COUNTER.load(Ordering::Relaxed),
+            counter_wraps: COUNTER_WRAPS.load(Ordering::Relaxed),
+        }
+    }
+}
+
+/// Records a generated ID with its counter value.
+pub(crate) fn record(counter: u32, wrapped: bool) {
+    GENERATED.fetch_add(1, Ordering::Relaxed);
+    PEAK_COUNTER.fetch_max(counter, Ordering::Relaxed);
+    if wrapped {
+        COUNTER_WRAPS.fetch_add(1, Ordering::Relaxed);
+    }
+}
+
+#[cfg(all(test, feature = "std"))]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn metrics_test() {
+        let before = Puid::metrics();
+        let builder = Puid::builder()
+            .prefix("foo")
+            .unwrap()
+            .counter_start(u32::MAX - 5);
+        for id in (&builder).into_iter().take(10) {
+            id.unwrap();
+        }
+
+        let after = Puid::metrics();
+        assert!(after.generated >= before.generated + 10);
+        assert_eq!(after.peak_counter, u32::MAX);
+        assert!(after.counter_wraps > before.counter_wraps);
+    }
+}
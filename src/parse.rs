@@ -0,0 +1,170 @@
+use crate::{
+    errors::{PuidError, PuidResult},
+    format::Fields,
+    Puid,
+};
+use alloc::string::{String, ToString};
+
+/// The components of an ID.
+///
+/// `Puid::parse` only accepts versioned IDs, whose fields are all `Some`
+/// except `process_id` when left out with `include_pid(false)`.
+/// IDs parsed from the legacy layout, written before the version marker,
+/// have no recoverable field boundaries: only the prefix is known and the
+/// whole body is kept as `random`, while `version`, `timestamp_ms`, `counter`
+/// and `process_id` are `None`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct PuidParts {
+    /// The prefix, without the separator.
+    pub prefix: String,
+    /// The layout version, `None` for legacy IDs.
+    pub version: Option<u8>,
+    /// The timestamp in milliseconds since the UNIX epoch.
+    pub timestamp_ms: Option<u128>,
+    /// The counter value.
+    pub counter: Option<u32>,
+    /// The process ID.
+    pub process_id: Option<u128>,
+    /// The random sequence, or the whole body for legacy IDs.
+    pub random: String,
+}
+
+impl From<Fields<'_>> for PuidParts {
+    fn from(fields: Fields<'_>) -> Self {
+        // The fields were validated as base-36 when splitting
+        let decode = |s| fields.decode(s).unwrap();
+        PuidParts {
+            prefix: fields.prefix.to_string(),
+            version: Some(fields.version),
+            timestamp_ms: fields.time_ms(),
+            counter: u32::try_from(decode(fields.counter)).ok(),
+            process_id: fields.process_id.map(decode),
+            random: fields.random.to_string(),
+        }
+    }
+}
+
+impl Puid {
+    /// Parses a versioned ID into its components.
+    ///
+    /// The separator is the first non-alphanumeric character and the
+    /// timestamp, counter and process ID are split by the widths the
+    /// generator pads them to. Returns `PuidError::Malformed` if the layout
+    /// does not match.
+    pub fn parse(id: &str) -> PuidResult<PuidParts> {
+        Fields::split(id).map(PuidParts::from)
+    }
+
+    /// Parses an ID into its components, falling back to the legacy layout
+    /// when the ID has no version marker.
+    ///
+    /// Legacy IDs only recover their prefix and body, see `PuidParts`.
+    /// Returns `PuidError::Malformed` if the ID has no prefix or body.
+    pub fn parse_lenient(id: &str) -> PuidResult<PuidParts> {
+        if let Ok(parts) = Puid::parse(id) {
+            return Ok(parts);
+        }
+
+        let prefix = Puid::prefix_of(id)?;
+        let rest = &id[prefix.len()..];
+        let body = rest.char_indices().nth(1).map_or(rest.len(), |(i, _)| i);
+        let body = &rest[body..];
+        if body.is_empty() || !body.chars().all(|c| c.is_ascii_alphanumeric()) {
+            return Err(PuidError::Malformed);
+        }
+        Ok(PuidParts {
+            prefix: prefix.to_string(),
+            version: None,
+            timestamp_ms: None,
+            counter: None,
+            process_id: None,
+            random: body.to_string(),
+        })
+    }
+}
+
+#[cfg(all(test, feature = "std"))]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_test() {
+        let id = Puid::builder()
+            .prefix("foo")
+            .unwrap()
+            .build_with_time(1651312057000)
+            .unwrap();
+        let parts = Puid::parse(&id).unwrap();
+        assert_eq!(parts.prefix, "foo");
+        assert_eq!(parts.version, Some(1));
+        assert_eq!(parts.timestamp_ms, Some(1651312057000));
+        assert!(parts.counter.is_some());
+        assert_eq!(parts.process_id, Some(u128::from(std::process::id())));
+        assert_eq!(parts.random, &id[id.len() - parts.random.len()..]);
+
+        #[allow(deprecated)]
+        let legacy = crate::puid("foo", 12);
+        assert!(Puid::parse(&legacy).is_err());
+        assert!(Puid::parse("foo_1").is_err());
+        assert!(Puid::parse("_10mv7m8kkc00000010000aj8EpBvUBU1dj8X").is_err());
+    }
+
+    #[test]
+    fn parse_lenient_v1_test() {
+        let parts = Puid::parse_lenient("foo_10mv7m8kkc00000010000aj8EpBvUBU1dj8X").unwrap();
+        assert_eq!(
+            parts,
+            PuidParts {
+                prefix: "foo".to_string(),
+                version: Some(1),
+                timestamp_ms: Some(1791952477212),
+                counter: Some(1),
+                process_id: Some(13652),
+                random: "EpBvUBU1dj8X".to_string(),
+            }
+        );
+    }
+
+    #[test]
+    fn parse_lenient_self_describing_test() {
+        for entropy in [8, 40] {
+            let id = Puid::builder()
+                .prefix("foo")
+                .unwrap()
+                .entropy(entropy)
+                .self_describing(true)
+                .build()
+                .unwrap();
+            assert!(id.starts_with("foo_20g"));
+            let parts = Puid::parse_lenient(&id).unwrap();
+            assert_eq!(parts.random.len(), entropy);
+        }
+
+        let id = Puid::builder()
+            .prefix("foo")
+            .unwrap()
+            .self_describing(true)
+            .build()
+            .unwrap();
+        assert!(Puid::parse_lenient(&id[..id.len() - 1])
+            .unwrap()
+            .version
+            .is_none());
+    }
+
+    #[test]
+    fn parse_lenient_legacy_test() {
+        #[allow(deprecated)]
+        let id = crate::puid("foo", 12);
+        let parts = Puid::parse_lenient(&id).unwrap();
+        assert_eq!(parts.prefix, "foo");
+        assert_eq!(parts.version, None);
+        assert_eq!(parts.timestamp_ms, None);
+        assert_eq!(parts.counter, None);
+        assert_eq!(parts.process_id, None);
+        assert_eq!(parts.random, &id[4..]);
+
+        assert!(Puid::parse_lenient("foo").is_err());
+        assert!(Puid::parse_lenient("foo_").is_err());
+    }
+}
@@ -0,0 +1,107 @@
+use crate::sync::Mutex;
+use crate::{errors::PuidResult, PuidBuilder};
+use alloc::{string::String, vec::Vec};
+use core::ops::Deref;
+
+/// A generator recycling the buffers of its IDs, for very high rates.
+///
+/// `acquire` fills a buffer from the pool, or a new one when it is empty, and
+/// the returned guard gives the buffer back on drop, so steady-state
+/// generation does not allocate the ID strings.
+#[derive(Debug)]
+pub struct PuidPool<'a> {
+    builder: PuidBuilder<'a>,
+    buffers: Mutex<Vec<String>>,
+}
+
+/// An ID borrowed from a `PuidPool`, returning its buffer on drop.
+#[derive(Debug)]
+pub struct PooledPuid<'p, 'a> {
+    pool: &'p PuidPool<'a>,
+    id: String,
+}
+
+impl<'a> PuidPool<'a> {
+    /// Creates an empty pool generating IDs with `builder`.
+    #[must_use]
+    pub fn new(builder: PuidBuilder<'a>) -> Self {
+        PuidPool {
+            builder,
+            buffers: Mutex::new(Vec::new()),
+        }
+    }
+
+    /// Generates an ID into a recycled buffer.
+    pub fn acquire(&self) -> PuidResult<PooledPuid<'_, 'a>> {
+        let mut id = self.buffers.lock().unwrap().pop().unwrap_or_default();
+        if let Err(err) = self.builder.generate_into(&mut id) {
+            self.release(id);
+            return Err(err);
+        }
+        Ok(PooledPuid { pool: self, id })
+    }
+
+    /// Returns the number of idle buffers in the pool.
+    #[must_use]
+    pub fn idle(&self) -> usize {
+        self.buffers.lock().unwrap().len()
+    }
+
+    /// Gives a buffer back to the pool.
+    fn release(&self, id: String) {
+        self.buffers.lock().unwrap().push(id);
+    }
+}
+
+impl Deref for PooledPuid<'_, '_> {
+    type Target = str;
+
+    fn deref(&self) -> &str {
+        &self.id
+    }
+}
+
+impl Drop for PooledPuid<'_, '_> {
+    fn drop(&mut self) {
+        self.pool.release(core::mem::take(&mut self.id));
+    }
+}
+
+#[cfg(all(test, feature = "std"))]
+mod tests {
+    use super::*;
+    use crate::{tracking::allocations, Puid};
+    use std::collections::HashSet;
+
+    #[test]
+    fn puid_pool_test() {
+        let pool = PuidPool::new(Puid::builder().prefix("foo").unwrap());
+        // Initializes the thread-local state of the generator beforehand
+        drop(pool.acquire().unwrap());
+        let pooled = allocations(|| {
+            for _ in 0..1000 {
+                let id = pool.acquire().unwrap();
+                assert!(id.starts_with("foo_"));
+            }
+        });
+        assert_eq!(pool.idle(), 1);
+
+        let builder = Puid::builder().prefix("foo").unwrap();
+        let built = allocations(|| {
+            for _ in 0..1000 {
+                builder.generate().unwrap();
+            }
+        });
+        // The pooled buffer is reused by every ID
+        assert!(pooled + 1000 <= built);
+    }
+
+    #[test]
+    fn puid_pool_unique_test() {
+        let pool = PuidPool::new(Puid::builder().prefix("foo").unwrap());
+        let ids: HashSet<String> = (0..1000)
+            .map(|_| pool.acquire().unwrap().to_string())
+            .collect();
+        assert_eq!(ids.len(), 1000);
+    }
+}
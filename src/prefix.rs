@@ -0,0 +1,70 @@
+use crate::{errors::PuidResult, Puid, PuidBuilder};
+use alloc::string::String;
+
+/// A type that names the prefix of its IDs, usable as a trait object, e.g.
+/// by heterogeneous collections of entities.
+pub trait DynPrefix {
+    /// Returns the prefix of the IDs of this value.
+    fn prefix(&self) -> &str;
+}
+
+impl Puid {
+    /// Builds an ID with the prefix of `value` without knowing its concrete
+    /// type.
+    ///
+    /// Returns `PuidError::InvalidPrefix` if the prefix fails validation.
+    pub fn build_for_dyn(value: &dyn DynPrefix, entropy: u8) -> PuidResult<String> {
+        PuidBuilder::new()
+            .prefix(value.prefix())?
+            .entropy(usize::from(entropy))
+            .build()
+    }
+}
+
+#[cfg(all(test, feature = "std"))]
+mod tests {
+    use super::*;
+    use crate::errors::PuidError;
+
+    struct User;
+
+    impl DynPrefix for User {
+        fn prefix(&self) -> &str {
+            "user"
+        }
+    }
+
+    struct Order {
+        region: String,
+    }
+
+    impl DynPrefix for Order {
+        fn prefix(&self) -> &str {
+            &self.region
+        }
+    }
+
+    #[test]
+    fn build_for_dyn_test() {
+        let entities: Vec<Box<dyn DynPrefix>> = vec![
+            Box::new(User),
+            Box::new(Order {
+                region: "eu".to_string(),
+            }),
+        ];
+        let ids: Vec<String> = entities
+            .iter()
+            .map(|entity| Puid::build_for_dyn(entity.as_ref(), 12).unwrap())
+            .collect();
+        assert!(ids[0].starts_with("user_"));
+        assert!(ids[1].starts_with("eu_"));
+
+        let invalid = Order {
+            region: "eu-west".to_string(),
+        };
+        assert!(matches!(
+            Puid::build_for_dyn(&invalid, 12),
+            Err(PuidError::InvalidPrefix)
+        ));
+    }
+}
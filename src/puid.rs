@@ -1,17 +1,168 @@
-use crate::errors::{PuidError, PuidResult};
-use rand::{distributions::Alphanumeric, thread_rng, Rng};
+use crate::{
+    clock::Clock,
+    errors::{PuidError, PuidResult},
+    format::{prefix_len, Encoding, Fields},
+    fpe, iso,
+    parse::PuidParts,
+    scramble::scramble,
+    sync::Mutex,
+    PuidId,
+};
+use alloc::{
+    borrow::Cow,
+    boxed::Box,
+    collections::BTreeSet,
+    string::{String, ToString},
+    vec,
+    vec::Vec,
+};
+use core::{
+    cell::RefCell,
+    sync::atomic::{AtomicBool, AtomicU32, AtomicU64, Ordering},
+    time::Duration,
+};
+#[cfg(feature = "std")]
+use rand::thread_rng;
+use rand::SeedableRng;
+use rand::{distributions::Alphanumeric, rngs::StdRng, Rng, RngCore};
+#[cfg(feature = "std")]
 use std::{
-    sync::atomic::{AtomicU8, Ordering},
-    time::{SystemTime, UNIX_EPOCH},
+    collections::HashSet,
+    sync::OnceLock,
+    time::{Instant, SystemTime, UNIX_EPOCH},
 };
 
 // Shared state that requires a stable memory location
-static COUNTER: AtomicU8 = AtomicU8::new(0);
+pub(crate) static COUNTER: AtomicU32 = AtomicU32::new(0);
+// Process-lifetime sequence replacing the timestamp in monotonic mode
+pub(crate) static SEQUENCE: AtomicU64 = AtomicU64::new(0);
+// Source of the internal IDs paired with public ones by `PuidBuilder::dual`
+static INTERNAL_ID: AtomicU64 = AtomicU64::new(1);
+// Source of the identifiers assigned to threads on first use
+#[cfg(feature = "std")]
+static NEXT_THREAD_ID: AtomicU32 = AtomicU32::new(1);
+// Millisecond after the last counter wrap plus one, 0 if it never wrapped
+static WRAP_FLOOR: AtomicU64 = AtomicU64::new(0);
+// Random node ID standing in for an unavailable process ID
+#[cfg(feature = "std")]
+static NODE_ID: OnceLock<u32> = OnceLock::new();
+static PID_FALLBACK: AtomicBool = AtomicBool::new(false);
+
+// IDs generated so far by a deduplicating builder, ordered without `std`
+#[cfg(feature = "std")]
+type Seen = HashSet<String>;
+#[cfg(not(feature = "std"))]
+type Seen = BTreeSet<String>;
+
+#[cfg(feature = "std")]
+thread_local! {
+    // Identifier of the current thread and its own counter
+    static THREAD_ID: u32 = NEXT_THREAD_ID.fetch_add(1, Ordering::Relaxed);
+    static THREAD_COUNTER: AtomicU32 = const { AtomicU32::new(0) };
+}
 
 const BASE_36: u8 = 36;
-const DEFAULT_ENTROPY: u8 = 12;
+/// Version of the layout written after the separator.
+pub(crate) const VERSION: u8 = 1;
+/// Version of the layout carrying optional segments, described by a
+/// fixed-width flags field written after the version.
+pub(crate) const EXTENDED_VERSION: u8 = 2;
+/// Largest value of the flags field, two base-36 characters.
+pub(crate) const FLAGS_MAX: u128 = 1295;
+/// Version of the extended layout whose flags field has three characters,
+/// used once the flags outgrow two.
+pub(crate) const WIDE_VERSION: u8 = 3;
+/// Largest value of the flags field of the wide layout, three base-36 characters.
+pub(crate) const WIDE_FLAGS_MAX: u128 = 46655;
+/// Flag set when the last character of the ID is the prefix length.
+pub(crate) const FLAG_LENGTH_HEADER: u16 = 1;
+/// Flag set when a thread ID follows the process ID.
+pub(crate) const FLAG_THREAD_ID: u16 = 2;
+/// Flag set when a user-defined tag follows the process and thread IDs.
+pub(crate) const FLAG_TAG: u16 = 4;
+/// Flag set when the fields are scrambled with the random sequence.
+pub(crate) const FLAG_SCRAMBLED: u16 = 8;
+/// Flag set when the entropy follows the version marker.
+pub(crate) const FLAG_ENTROPY: u16 = 16;
+/// Flag set when a namespace hash follows the tag.
+pub(crate) const FLAG_NAMESPACE: u16 = 32;
+/// Flag set when the timestamp is written in ISO-8601 form.
+pub(crate) const FLAG_ISO_TIME: u16 = 64;
+/// Flag set when the separator is repeated between the fields.
+pub(crate) const FLAG_DELIMITED: u16 = 128;
+/// Flag set when a node ID follows the namespace hash.
+pub(crate) const FLAG_NODE_ID: u16 = 256;
+/// Flag set when the timestamp resolution follows the entropy.
+pub(crate) const FLAG_RESOLUTION: u16 = 512;
+/// Flag set when the process ID is omitted.
+pub(crate) const FLAG_NO_PID: u16 = 1024;
+/// Flag set when an expiry timestamp follows the node ID.
+pub(crate) const FLAG_EXPIRY: u16 = 2048;
+/// Flag set when the fields are encoded in Crockford base-32.
+pub(crate) const FLAG_CROCKFORD: u16 = 4096;
+/// Flag set when the timestamp is left out.
+pub(crate) const FLAG_NO_TIME: u16 = 8192;
+/// Flag set when an installation ID follows the node ID.
+pub(crate) const FLAG_INSTALLATION: u16 = 16384;
+/// Flag set when the fields are encoded in the readable base-32 alphabet.
+pub(crate) const FLAG_READABLE: u16 = 32768;
+/// Largest timestamp the fixed-width field is sized for (year 5188 in milliseconds).
+pub(crate) const TIME_MAX: u128 = 101_559_956_668_415;
+/// Largest digit identifying a timestamp resolution.
+const RESOLUTION_MAX: u128 = 2;
+pub(crate) const DEFAULT_SEPARATOR: char = '_';
+#[cfg(all(feature = "default-entropy-16", feature = "default-entropy-24"))]
+compile_error!("features `default-entropy-16` and `default-entropy-24` are mutually exclusive");
+#[cfg(not(any(feature = "default-entropy-16", feature = "default-entropy-24")))]
+pub(crate) const DEFAULT_ENTROPY: u8 = 12;
+#[cfg(feature = "default-entropy-16")]
+pub(crate) const DEFAULT_ENTROPY: u8 = 16;
+#[cfg(all(feature = "default-entropy-24", not(feature = "default-entropy-16")))]
+pub(crate) const DEFAULT_ENTROPY: u8 = 24;
+// KSUID epoch in seconds since the UNIX epoch (2014-05-13T16:53:20Z)
+const KSUID_EPOCH: u128 = 1_400_000_000;
+// Base-62 characters of a KSUID body
+const KSUID_LEN: usize = 27;
+const DECIMAL: &str = "0123456789";
+/// Digits of Crockford base-32, without the ambiguous `I`, `L`, `O` and `U`.
+pub(crate) const CROCKFORD: &str = "0123456789ABCDEFGHJKMNPQRSTVWXYZ";
+const BASE_62: &[u8] = b"0123456789ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz";
+// Retries after a duplicate before giving up
+const MAX_DEDUP_RETRIES: u8 = 16;
+
+/// Maximum entropy (random characters) of an ID. Larger entropies fail with
+/// `PuidError::EntropyOutOfRange`, or `PuidError::EntropyTooLarge` when
+/// retries grow the entropy past it.
+pub const MAX_ENTROPY: usize = 1024;
+// Entropy generated into a stack buffer, longer sequences go to the heap
+const STACK_ENTROPY: usize = 128;
+// Base-36 characters needed for a `u128`
+const OPAQUE_LEN: usize = 25;
 const PREFIX_MAX_LEN: usize = 8;
+// Largest maximum length of the prefix configurable with `max_prefix_len`
+pub(crate) const PREFIX_MAX_LEN_LIMIT: usize = 32;
 const PREFIX_MIN_LEN: usize = 1;
+// Characters of URL-safe base64, `A-Za-z0-9-_`, 6 bits each
+pub(crate) const BASE_64_URL: &str =
+    "ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789-_";
+// Separator used with the URL-safe base64 tail, which includes the default one
+const BASE_64_URL_SEPARATOR: char = '.';
+// Lowercase characters without the ambiguous `0/o/1/l`, 5 bits each
+pub(crate) const READABLE_ALPHABET: &str = "23456789abcdefghijkmnpqrstuvwxyz";
+// Characters per hyphen-separated group of the readable random sequence
+const READABLE_GROUP_LEN: usize = 4;
+// Uppercase characters without those OCR confuses, `0/O/Q/D`, `1/I/L`,
+// `2/Z`, `5/S` and `8/B`
+const OCR_ALPHABET: &str = "034679ACEFGHJKMNPRTUVWXY";
+// Characters of the alphanumeric mode of QR codes
+const QR_ALPHABET: &str = "0123456789ABCDEFGHIJKLMNOPQRSTUVWXYZ $%*+-./:";
+const QR_SEPARATOR: char = '-';
+#[cfg(feature = "testing")]
+// Fixed point in time used by the deterministic mode (2022-04-30T09:47:37Z).
+const DETERMINISTIC_TIME: u128 = 1_651_312_057_000;
+#[cfg(feature = "testing")]
+// Process ID embedded by the deterministic mode instead of the current one
+const DETERMINISTIC_PID: u32 = 1;
 
 /// The exposed struct for generate Puids.
 pub struct Puid;
@@ -22,217 +173,5272 @@ impl Puid {
     pub fn builder() -> PuidBuilder<'static> {
         PuidBuilder::new()
     }
+
+    /// Returns a builder preconfigured with the separator, encoding, entropy
+    /// and layout approximating `flavor`, still to be given a prefix.
+    ///
+    /// The IDs keep the Puid layout, so they are not interchangeable with
+    /// the scheme they resemble, see `PuidBuilder::ksuid_compat` for that.
+    #[must_use]
+    pub fn builder_with_flavor(flavor: Flavor) -> PuidBuilder<'static> {
+        let builder = PuidBuilder::new();
+        match flavor {
+            Flavor::Default => builder,
+            Flavor::StripeLike => builder.entropy(24),
+            Flavor::NanoidLike => builder.charset(CharSet::Base64Url).entropy(21),
+            Flavor::UlidLike => {
+                let mut builder = builder
+                    .crockford_base32(true)
+                    .include_pid(false)
+                    .sortable(true)
+                    .entropy(16);
+                builder.alphabet = Some(CROCKFORD.chars().collect());
+                builder
+            }
+            Flavor::KsuidLike => builder
+                .resolution(TimeResolution::Seconds)
+                .include_pid(false)
+                .sortable(true)
+                .entropy(22),
+        }
+    }
+
+    /// Returns a short-lived token packing the low 56 bits of the time in
+    /// milliseconds and the low 8 bits of the global counter, without
+    /// allocating.
+    ///
+    /// Tokens are unique within a process for up to 256 tokens per
+    /// millisecond, shared with the IDs advancing the same counter, and
+    /// carry no process ID or randomness, so they are only suited to
+    /// in-memory keys such as rate limiter buckets.
+    #[cfg(feature = "std")]
+    #[must_use]
+    #[allow(clippy::cast_possible_truncation)]
+    pub fn ephemeral_u64() -> u64 {
+        (time() as u64) << 8 | u64::from(next_by(&COUNTER, 1) & 0xff)
+    }
+
+    /// Generates IDs for several prefixes in one call, where each spec is
+    /// `(prefix, entropy, count)`, returning them flattened in spec order.
+    ///
+    /// All prefixes are validated before generating any ID, returning
+    /// `PuidError::InvalidPrefix` if one is invalid and
+    /// `PuidError::DuplicatePrefix` if one is listed twice.
+    pub fn build_mixed(specs: &[(&str, u8, usize)]) -> PuidResult<Vec<String>> {
+        Puid::build_mixed_with(specs, false)
+    }
+
+    /// Same as `build_mixed`, but a prefix can be listed more than once
+    /// when `allow_duplicate_prefixes` is set.
+    pub fn build_mixed_with(
+        specs: &[(&str, u8, usize)],
+        allow_duplicate_prefixes: bool,
+    ) -> PuidResult<Vec<String>> {
+        if !specs.iter().all(|(prefix, _, _)| validate(prefix)) {
+            return Err(PuidError::InvalidPrefix);
+        }
+        let mut prefixes = BTreeSet::new();
+        if !allow_duplicate_prefixes && !specs.iter().all(|(prefix, _, _)| prefixes.insert(prefix))
+        {
+            return Err(PuidError::DuplicatePrefix);
+        }
+
+        let mut ids = Vec::with_capacity(specs.iter().map(|(_, _, count)| count).sum());
+        for &(prefix, entropy, count) in specs {
+            let builder = PuidBuilder::new()
+                .prefix(prefix)?
+                .entropy(usize::from(entropy));
+            for id in builder.into_iter().take(count) {
+                ids.push(id?);
+            }
+        }
+        Ok(ids)
+    }
+
+    /// Computes the minimum entropy (random characters) keeping the collision
+    /// probability of `dataset_size` IDs below `max_collision_prob`.
+    ///
+    /// Solves the birthday bound `n(n - 1) / 2N <= p` for `N = alphabet_size^k`.
+    /// The default alphanumeric sequence has an `alphabet_size` of 62.
+    ///
+    /// Returns `u8::MAX` when the target is unreachable (an alphabet smaller
+    /// than 2 or a non positive probability).
+    #[must_use]
+    #[allow(
+        clippy::cast_precision_loss,
+        clippy::cast_possible_truncation,
+        clippy::cast_sign_loss
+    )]
+    pub fn min_entropy_for(dataset_size: u128, max_collision_prob: f64, alphabet_size: u32) -> u8 {
+        if dataset_size < 2 || max_collision_prob >= 1.0 {
+            return 0;
+        }
+        if alphabet_size < 2 || max_collision_prob <= 0.0 || max_collision_prob.is_nan() {
+            return u8::MAX;
+        }
+
+        let n = dataset_size as f64;
+        let space = n * (n - 1.0) / (2.0 * max_collision_prob);
+        let k = ceil(log2(space) / log2(f64::from(alphabet_size)));
+        k.clamp(0.0, f64::from(u8::MAX)) as u8
+    }
+
+    /// Generates an ID that is not in `existing`, e.g. when appending to a
+    /// dataset, generating again up to 16 times on a collision.
+    ///
+    /// Returns `PuidError::InvalidPrefix` for an invalid prefix and
+    /// `PuidError::Duplicate` if every attempt is already in `existing`.
+    #[cfg(feature = "std")]
+    pub fn build_avoiding(
+        prefix: &str,
+        entropy: u8,
+        existing: &HashSet<String>,
+    ) -> PuidResult<String> {
+        PuidBuilder::new()
+            .prefix(prefix)?
+            .entropy(usize::from(entropy))
+            .generate_avoiding(existing)
+    }
+
+    /// Returns whether the check character ending an ID built with
+    /// `PuidBuilder::ocr_safe` matches its body, e.g. after scanning it.
+    ///
+    /// Any single substituted character is detected, as are most swaps of
+    /// adjacent characters.
+    #[must_use]
+    pub fn verify_check_char(id: &str) -> bool {
+        let Some(at) = prefix_len(id) else {
+            return false;
+        };
+        let body = id[at..]
+            .char_indices()
+            .nth(1)
+            .map_or("", |(i, _)| &id[at + i..]);
+        !body.is_empty()
+            && body.ends_with(|c| OCR_ALPHABET.contains(c))
+            && luhn_sum(body, OCR_ALPHABET, 1).is_multiple_of(OCR_ALPHABET.len())
+    }
+
+    /// Derives an ID from a hash of `input` instead of the time and random
+    /// sources, so identical inputs always map to identical IDs, e.g. for
+    /// idempotency keys.
+    ///
+    /// The body is `entropy` alphanumeric characters of a stable 64-bit
+    /// FNV-1a hash chain, so the IDs are neither time-ordered nor secret, and
+    /// have no version marker. Returns `PuidError::InvalidPrefix` for an
+    /// invalid prefix and `PuidError::EntropyOutOfRange` above `MAX_ENTROPY`.
+    pub fn content_addressed(prefix: &str, input: &[u8], entropy: usize) -> PuidResult<String> {
+        if !validate(prefix) {
+            return Err(PuidError::InvalidPrefix);
+        }
+        if entropy > MAX_ENTROPY {
+            return Err(PuidError::EntropyOutOfRange);
+        }
+
+        let len = prefix.len() + 1 + entropy;
+        let mut id = String::with_capacity(len);
+        id.push_str(prefix);
+        id.push(DEFAULT_SEPARATOR);
+        let base = BASE_62.len() as u64;
+        let mut block = 0;
+        while id.len() < len {
+            // 10 base-62 digits fit in 64 bits
+            let mut hash = fnv1a_64(block, input);
+            for _ in 0..10.min(len - id.len()) {
+                id.push(char::from(BASE_62[(hash % base) as usize]));
+                hash /= base;
+            }
+            block += 1;
+        }
+        Ok(id)
+    }
+
+    /// Returns how many more IDs the global counter can number before it
+    /// wraps, i.e. `u32::MAX` minus its current value.
+    ///
+    /// This is advisory, as other threads may advance the counter meanwhile.
+    #[must_use]
+    pub fn counter_headroom() -> u32 {
+        headroom(&COUNTER)
+    }
+
+    /// Returns the entropy used by `PuidBuilder::new()`, 12 unless changed by
+    /// a `default-entropy-*` feature.
+    #[must_use]
+    pub fn default_entropy() -> u8 {
+        DEFAULT_ENTROPY
+    }
+
+    /// Returns whether a random node ID replaced an unavailable process ID.
+    ///
+    /// Some sandboxed platforms report a process ID of 0, in which case a
+    /// random 32-bit node ID generated once per process is used instead.
+    #[must_use]
+    pub fn using_pid_fallback() -> bool {
+        PID_FALLBACK.load(Ordering::Relaxed)
+    }
+}
+
+/// How a duplicate ID is mutated when retrying under deduplication.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum RetryStrategy {
+    /// Generates again, advancing the counter.
+    #[default]
+    BumpCounter,
+    /// Generates again with one more random character per retry.
+    BumpEntropy,
+    /// Generates again with the timestamp advanced 1 millisecond per retry.
+    AdvanceTime,
+    /// Fails with `PuidError::Duplicate` without retrying.
+    Fail,
+}
+
+/// A character set for the random sequence.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum CharSet {
+    /// The 62 alphanumeric characters, `0-9A-Za-z`.
+    #[default]
+    Alphanumeric,
+    /// The 64 characters of URL-safe base64, `A-Za-z0-9-_`, without padding.
+    ///
+    /// Each character carries 6 bits instead of about 5.95, so tails are
+    /// about 1% shorter for the same bits of entropy.
+    Base64Url,
+}
+
+/// A preset approximating a popular ID scheme.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Flavor {
+    /// The default layout with the default entropy of alphanumeric random
+    /// characters, 12 unless changed with the `default-entropy-*` features.
+    #[default]
+    Default,
+    /// Like Stripe IDs, whose body ends in 24 alphanumeric characters.
+    StripeLike,
+    /// Like Nano ID, 21 random characters of URL-safe base64, separated by
+    /// `.` since `_` is part of the alphabet.
+    NanoidLike,
+    /// Like ULID, sortable uppercase Crockford base-32 fields without the
+    /// process ID and 16 random characters of the same alphabet, 80 bits.
+    UlidLike,
+    /// Like KSUID, sortable second timestamps without the process ID and 22
+    /// alphanumeric random characters, about 128 bits.
+    KsuidLike,
+}
+
+/// The letter case of the random sequence.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Case {
+    /// Digits and lowercase letters, `0-9a-z`.
+    Lower,
+    /// Digits and uppercase letters, `0-9A-Z`.
+    Upper,
+    /// Digits and letters of both cases, `0-9A-Za-z`.
+    #[default]
+    Mixed,
+}
+
+/// The resolution of the timestamp embedded in an ID.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum TimeResolution {
+    /// Seconds since the UNIX epoch, in 8 base-36 characters.
+    Seconds,
+    /// Milliseconds since the UNIX epoch, in 9 base-36 characters.
+    #[default]
+    Millis,
+    /// Microseconds since the UNIX epoch, in 11 base-36 characters.
+    Micros,
+}
+
+impl TimeResolution {
+    /// Returns the current time in ticks of this resolution.
+    #[cfg(feature = "std")]
+    fn now(self) -> u128 {
+        let elapsed = SystemTime::now().duration_since(UNIX_EPOCH).unwrap();
+        match self {
+            TimeResolution::Seconds => u128::from(elapsed.as_secs()),
+            TimeResolution::Millis => elapsed.as_millis(),
+            TimeResolution::Micros => elapsed.as_micros(),
+        }
+    }
+
+    /// Converts milliseconds into ticks of this resolution.
+    fn ticks(self, ms: u128) -> u128 {
+        match self {
+            TimeResolution::Seconds => ms / 1000,
+            TimeResolution::Millis => ms,
+            TimeResolution::Micros => ms * 1000,
+        }
+    }
+
+    /// Converts ticks of this resolution into milliseconds.
+    pub(crate) fn millis(self, ticks: u128) -> u128 {
+        match self {
+            TimeResolution::Seconds => ticks * 1000,
+            TimeResolution::Millis => ticks,
+            TimeResolution::Micros => ticks / 1000,
+        }
+    }
+
+    /// Returns the largest timestamp the fixed-width field is sized for.
+    pub(crate) fn max(self) -> u128 {
+        self.ticks(TIME_MAX)
+    }
+
+    /// Returns the digit identifying this resolution in an ID.
+    fn code(self) -> u128 {
+        self as u128
+    }
+
+    /// Returns the resolution identified by a digit of an ID.
+    pub(crate) fn from_code(code: u128) -> Option<Self> {
+        match code {
+            0 => Some(TimeResolution::Seconds),
+            1 => Some(TimeResolution::Millis),
+            2 => Some(TimeResolution::Micros),
+            _ => None,
+        }
+    }
+}
+
+/// Values fixed for a single ID instead of drawn from the builder sources.
+#[derive(Clone, Copy, Debug, Default)]
+struct Pins<'p> {
+    // Retry of a duplicate, mutating the ID according to the strategy.
+    retry: u8,
+    // Time used instead of the clock or the monotonic sequence.
+    time: Option<u128>,
+    // Timestamp in ticks of the resolution, written without converting it.
+    ticks: Option<u128>,
+    // Counter value used instead of advancing the counter.
+    counter: Option<u32>,
+    // Receives the random bytes the random sequence is encoded from, instead
+    // of sampling its characters.
+    bytes: Option<&'p RefCell<Vec<u8>>>,
+    // Receives the components of the ID as they are written.
+    parts: Option<&'p RefCell<Option<PuidParts>>>,
 }
 
+/// A transform applied to every generated ID.
+type Transform<'a> = Box<dyn Fn(String) -> String + Send + Sync + 'a>;
+
 /// A builder struct for constructing puids.
 #[allow(clippy::module_name_repetitions)]
-#[derive(Debug, Default)]
+#[derive(Default)]
 pub struct PuidBuilder<'a> {
-    entropy: u8,
-    prefix: &'a str,
+    entropy: usize,
+    // Largest entropy when it varies per ID, from `entropy` up.
+    entropy_max: Option<usize>,
+    prefix: Cow<'a, str>,
+    // Minimum length of the prefix, `PREFIX_MIN_LEN` if unset.
+    min_prefix_len: Option<usize>,
+    // Maximum length of the prefix, `PREFIX_MAX_LEN` if unset.
+    max_prefix_len: Option<usize>,
+    // Check replacing the alphanumeric and length rules of the prefix.
+    prefix_validator: Option<fn(&str) -> bool>,
+    // Locale segment following the prefix, lowercase and without hyphen.
+    locale: Option<String>,
+    // Separator between the prefix and the body, `_` if unset.
+    separator: Option<char>,
+    // Maximum length of the whole ID, if capped.
+    max_total_len: Option<usize>,
+    // Maximum length of the whole ID in UTF-8 bytes, if capped.
+    max_bytes: Option<usize>,
+    // Digits used for the timestamp, counter and process ID instead of base-36.
+    digits: Option<Vec<char>>,
+    // Characters used for the random sequence instead of `Alphanumeric`.
+    alphabet: Option<Vec<char>>,
+    // Length of the hyphen-separated groups of the random sequence.
+    group_len: Option<usize>,
+    // Whether the whole ID is kept lowercase.
+    lowercase: bool,
+    // Whether a check character for OCR errors ends the ID.
+    ocr_safe: bool,
+    // Whether the ID only uses the alphanumeric mode of QR codes.
+    qr_alphanumeric: bool,
+    // Whether the timestamp is replaced by the process-lifetime sequence.
+    monotonic: bool,
+    // Whether the ID ends with the prefix length.
+    length_header: bool,
+    // Whether the timestamp is written in ISO-8601 form instead of base-36.
+    iso_timestamp: bool,
+    // Resolution of the timestamp, milliseconds by default.
+    resolution: TimeResolution,
+    // Whether options breaking the lexical time order are rejected.
+    sortable: bool,
+    // Whether the separator is repeated between the fields.
+    delimited: bool,
+    // Whether the ID is made of digits only, without separator.
+    numeric: bool,
+    // Whether the ID is intentionally written without prefix and separator.
+    no_prefix: bool,
+    // Whether the process ID is left out.
+    no_pid: bool,
+    // Whether the timestamp is left out.
+    no_timestamp: bool,
+    // Whether the fields are encoded in Crockford base-32.
+    crockford: bool,
+    // Whether the fields are encoded in the readable base-32 alphabet.
+    readable: bool,
+    // Whether a thread ID follows the process ID, with a per-thread counter.
+    thread_id: bool,
+    // User-defined value following the process and thread IDs.
+    tag: Option<u16>,
+    // Hash of the namespace following the tag.
+    namespace: Option<u32>,
+    // Caller-supplied machine identifier following the namespace.
+    node_id: Option<u16>,
+    // Caller-supplied client installation identifier following the node ID.
+    installation_id: Option<u64>,
+    // Time to live, embedded as an expiry timestamp following the installation ID.
+    ttl: Option<Duration>,
+    // Whether the body is replaced by a hash of its components.
+    opaque: bool,
+    // Whether the entropy follows the version marker.
+    self_describing: bool,
+    // Whether the body is aligned to decode as base64url.
+    decodable: bool,
+    // Whether the fields are scrambled so sequential IDs look unrelated.
+    scramble: bool,
+    // Key of the cipher encrypting the timestamp.
+    time_key: Option<u64>,
+    // Whether the random sequence is mapped from a single buffer of bytes.
+    bulk_random: bool,
+    // Fixed time in milliseconds used instead of the system clock.
+    time: Option<u128>,
+    // Clock read instead of the system one, unless the time is fixed.
+    clock: Option<Box<dyn Clock + 'a>>,
+    // Process ID used instead of the current one.
+    pid: Option<u32>,
+    // Per-builder counter used instead of the global one.
+    counter: Option<AtomicU32>,
+    // Second last observed and the next counter value within it, when the
+    // counter restarts every second.
+    second_counter: Option<Mutex<(u128, u32)>>,
+    // Increment applied to the counter, 1 if unset.
+    counter_step: Option<u8>,
+    // Seeded generator used instead of `thread_rng()`.
+    rng: Option<Mutex<StdRng>>,
+    // Seed of the generator, mixed with the namespace hash when seeding it.
+    seed: Option<u64>,
+    // Post-processing applied to the final string.
+    transform: Option<Transform<'a>>,
+    // Expected number of IDs per batch, if hinted.
+    reserve: Option<usize>,
+    // IDs generated so far, when deduplicating.
+    seen: Option<Mutex<Seen>>,
+    // How duplicates are retried.
+    strategy: RetryStrategy,
+    // Constant ID returned by `build_cow` instead of generating one.
+    placeholder: Option<&'static str>,
+    // Whether the options passed `validate` since they were last set, so
+    // IDs written into a buffer skip the checks.
+    validated: AtomicBool,
+}
+
+impl<'a> core::fmt::Debug for PuidBuilder<'a> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_struct("PuidBuilder")
+            .field("entropy", &self.entropy)
+            .field("entropy_max", &self.entropy_max)
+            .field("prefix", &self.prefix)
+            .field("min_prefix_len", &self.min_prefix_len)
+            .field("max_prefix_len", &self.max_prefix_len)
+            .field("prefix_validator", &self.prefix_validator.is_some())
+            .field("locale", &self.locale)
+            .field("separator", &self.separator)
+            .field("max_total_len", &self.max_total_len)
+            .field("max_bytes", &self.max_bytes)
+            .field("digits", &self.digits)
+            .field("alphabet", &self.alphabet)
+            .field("group_len", &self.group_len)
+            .field("lowercase", &self.lowercase)
+            .field("ocr_safe", &self.ocr_safe)
+            .field("qr_alphanumeric", &self.qr_alphanumeric)
+            .field("monotonic", &self.monotonic)
+            .field("length_header", &self.length_header)
+            .field("iso_timestamp", &self.iso_timestamp)
+            .field("resolution", &self.resolution)
+            .field("sortable", &self.sortable)
+            .field("delimited", &self.delimited)
+            .field("numeric", &self.numeric)
+            .field("no_prefix", &self.no_prefix)
+            .field("no_pid", &self.no_pid)
+            .field("no_timestamp", &self.no_timestamp)
+            .field("crockford", &self.crockford)
+            .field("readable", &self.readable)
+            .field("thread_id", &self.thread_id)
+            .field("tag", &self.tag)
+            .field("namespace", &self.namespace)
+            .field("node_id", &self.node_id)
+            .field("installation_id", &self.installation_id)
+            .field("ttl", &self.ttl)
+            .field("opaque", &self.opaque)
+            .field("self_describing", &self.self_describing)
+            .field("decodable", &self.decodable)
+            .field("scramble", &self.scramble)
+            .field("time_key", &self.time_key.is_some())
+            .field("bulk_random", &self.bulk_random)
+            .field("time", &self.time)
+            .field("clock", &self.clock.is_some())
+            .field("pid", &self.pid)
+            .field("counter", &self.counter)
+            .field("second_counter", &self.second_counter)
+            .field("counter_step", &self.counter_step)
+            .field("rng", &self.rng)
+            .field("seed", &self.seed)
+            .field("transform", &self.transform.is_some())
+            .field("reserve", &self.reserve)
+            .field("seen", &self.seen.is_some())
+            .field("strategy", &self.strategy)
+            .field("placeholder", &self.placeholder)
+            .field("validated", &self.validated)
+            .finish()
+    }
 }
 
 impl<'a> PuidBuilder<'a> {
     /// Creates a new instance of `PuidBuilder` with default entropy.
+    ///
+    /// Without `std` there is no process ID to read, so it is left out
+    /// unless injected.
     pub fn new() -> Self {
         Self {
-            entropy: DEFAULT_ENTROPY,
+            entropy: usize::from(DEFAULT_ENTROPY),
+            no_pid: cfg!(not(feature = "std")),
             ..Self::default()
         }
     }
 
+    /// Creates a builder producing IDs with the same structure as `id`: its
+    /// prefix, separator, encoding, layout flags and entropy.
+    ///
+    /// The flags are recovered from base-36 and Crockford base-32 IDs only,
+    /// and a namespace is carried over as its hash. Returns
+    /// `PuidError::Malformed` if `id` does not match a versioned layout,
+    /// including legacy IDs, and
+    /// `PuidError::InvalidPrefix` if its prefix is not a valid one.
+    pub fn infer_from(id: &str) -> PuidResult<PuidBuilder<'static>> {
+        let format = Puid::detect_format(id)?;
+        if format.version.is_none() {
+            return Err(PuidError::Malformed);
+        }
+        // An ID without a separator has no prefix
+        let prefix = match prefix_len(id) {
+            Some(_) => Puid::prefix_of(id)?,
+            None => "",
+        };
+        if !prefix.is_empty() && !validate_with(prefix, PREFIX_MAX_LEN_LIMIT) {
+            return Err(PuidError::InvalidPrefix);
+        }
+
+        let mut builder = PuidBuilder {
+            prefix: Cow::Owned(prefix.to_string()),
+            max_prefix_len: (prefix.len() > PREFIX_MAX_LEN).then_some(prefix.len()),
+            no_prefix: prefix.is_empty(),
+            ..PuidBuilder::default()
+        };
+        if format.separator != DEFAULT_SEPARATOR {
+            builder.separator = Some(format.separator);
+        }
+        match format.encoding {
+            Encoding::Base62 => {
+                builder.digits = Some(BASE_62.iter().copied().map(char::from).collect());
+            }
+            Encoding::Crockford => builder = builder.crockford_base32(true),
+            Encoding::Readable => builder = builder.readable(),
+            Encoding::Base36 => {}
+        }
+        if let Ok(fields) = Fields::split(id) {
+            let has = |flag: u16| fields.flags & flag != 0;
+            builder.length_header = has(FLAG_LENGTH_HEADER);
+            builder.no_pid = has(FLAG_NO_PID);
+            builder.no_timestamp = has(FLAG_NO_TIME);
+            builder.thread_id = has(FLAG_THREAD_ID);
+            builder.scramble = has(FLAG_SCRAMBLED);
+            builder.self_describing = has(FLAG_ENTROPY);
+            builder.iso_timestamp = has(FLAG_ISO_TIME);
+            builder.delimited = has(FLAG_DELIMITED);
+            builder.resolution = fields.resolution;
+            builder.locale = fields.locale.map(str::to_string);
+            builder.tag = fields
+                .tag
+                .and_then(|field| fields.decode(field))
+                .and_then(|tag| u16::try_from(tag).ok());
+            builder.namespace = fields
+                .namespace
+                .and_then(|field| fields.decode(field))
+                .and_then(|hash| u32::try_from(hash).ok());
+            builder.node_id = fields
+                .node_id
+                .and_then(|field| fields.decode(field))
+                .and_then(|node| u16::try_from(node).ok());
+            builder.installation_id = fields
+                .installation
+                .and_then(|field| fields.decode(field))
+                .and_then(|installation| u64::try_from(installation).ok());
+            builder.ttl = fields
+                .expiry
+                .and_then(|field| fields.decode(field))
+                .map(|expiry| {
+                    // Without a timestamp the TTL is unknown, only the field is kept
+                    let ttl = expiry.saturating_sub(fields.time_ms().unwrap_or(expiry));
+                    Duration::from_millis(u64::try_from(ttl).unwrap_or(u64::MAX))
+                });
+        }
+
+        builder.entropy = id
+            .len()
+            .checked_sub(builder.len_with(0))
+            .ok_or(PuidError::Malformed)?;
+        Ok(builder)
+    }
+
     /// Sets the prefix if it passes validation.
     pub fn prefix(mut self, prefix: &'a str) -> PuidResult<Self> {
-        if validate(prefix) {
-            self.prefix = prefix;
+        *self.validated.get_mut() = false;
+        let valid = match self.prefix_validator {
+            Some(validator) => !prefix.is_empty() && validator(prefix),
+            None => {
+                validate_with(prefix, self.prefix_max_len())
+                    && prefix.len() >= self.prefix_min_len()
+            }
+        };
+        if valid {
+            self.prefix = Cow::Borrowed(prefix);
+            self.no_prefix = false;
             Ok(self)
         } else {
             Err(PuidError::InvalidPrefix)
         }
     }
 
-    /// Sets the entropy (length of random characters).
-    pub fn entropy(mut self, entropy: u8) -> Self {
-        self.entropy = entropy;
+    /// Omits the prefix and its separator, so the ID is only the body, e.g.
+    /// `10mv7m8kkc00000000000aj8OE3q1tEDrfSC` as an opaque token.
+    ///
+    /// Unlike an empty prefix, which fails with `PuidError::InvalidPrefix`,
+    /// this is an intentional choice, undone by setting a prefix. Parsers
+    /// read IDs without any separator as unprefixed, so delimited fields
+    /// cannot be parsed back.
+    pub fn no_prefix(mut self) -> Self {
+        *self.validated.get_mut() = false;
+        self.prefix = Cow::Borrowed("");
+        self.no_prefix = true;
         self
     }
 
-    /// Builds the final PUID string if prefix is valid.
-    pub fn build(self) -> PuidResult<String> {
-        if self.prefix.is_empty() {
+    /// Replaces the check of the prefix, 1 to 8 alphanumeric characters by
+    /// default, e.g. to allow dotted namespaces like `org.billing`. Set it
+    /// before the prefix, which is checked again by `build()`.
+    ///
+    /// The predicate enforces its own length rules, and an empty prefix is
+    /// always rejected. `build()` returns `PuidError::SeparatorCollision` if
+    /// the prefix contains the separator, which would make IDs ambiguous.
+    /// Parsing stops the prefix at its first non-alphanumeric character, so
+    /// IDs with other characters in the prefix cannot be parsed back.
+    pub fn prefix_validator(mut self, validator: fn(&str) -> bool) -> Self {
+        *self.validated.get_mut() = false;
+        self.prefix_validator = Some(validator);
+        self
+    }
+
+    /// Adds a locale segment after the prefix for localized content, e.g.
+    /// `post_enus_<body>` for `en-US`, read back by `Puid::locale_of`.
+    ///
+    /// The tag is a 2 or 3 letter language with an optional 2 letter region
+    /// after a `-` or `_`, in any case, and is written lowercase without the
+    /// hyphen. Returns `PuidError::InvalidLocale` for other tags.
+    pub fn locale(mut self, tag: &str) -> PuidResult<Self> {
+        *self.validated.get_mut() = false;
+        self.locale = Some(compact_locale(tag).ok_or(PuidError::InvalidLocale)?);
+        Ok(self)
+    }
+
+    /// Sets the minimum length of the prefix, 1 by default.
+    ///
+    /// A prefix set before is checked again by `build()`. Returns
+    /// `PuidError::InvalidPrefix` if `n` exceeds the maximum prefix length.
+    pub fn min_prefix_len(mut self, n: usize) -> PuidResult<Self> {
+        *self.validated.get_mut() = false;
+        if n > self.prefix_max_len() {
             return Err(PuidError::InvalidPrefix);
         }
+        self.min_prefix_len = Some(n);
+        Ok(self)
+    }
 
-        // self.prefix.len() for the prefix,
-        // 1 for the underscore _ separator
-        // 16 for the time value in base-36 (which is a reasonable upper bound)
-        // 3 for the counter value
-        // 16 for the process ID in base-36
-        // self.entropy for the random alphanumeric string
-        let mut result =
-            String::with_capacity(self.prefix.len() + 1 + 16 + 3 + 16 + self.entropy as usize);
-
-        result.push_str(self.prefix);
-        result.push('_');
-        result.push_str(&to_base36(time()));
-        result.push_str(&counter().to_string());
-        result.push_str(&to_base36(u128::from(std::process::id())));
-        result.push_str(&rnd_string(self.entropy));
+    /// Sets the maximum length of the prefix, 8 by default and up to 32,
+    /// e.g. for descriptive service names.
+    ///
+    /// A prefix set before is checked again by `build()`. Returns
+    /// `PuidError::InvalidPrefix` if `n` is below the minimum prefix length
+    /// or above 32.
+    pub fn max_prefix_len(mut self, n: usize) -> PuidResult<Self> {
+        *self.validated.get_mut() = false;
+        if n < self.prefix_min_len().max(PREFIX_MIN_LEN) || n > PREFIX_MAX_LEN_LIMIT {
+            return Err(PuidError::InvalidPrefix);
+        }
+        self.max_prefix_len = Some(n);
+        Ok(self)
+    }
 
-        Ok(result)
+    /// Sets the entropy (length of random characters), up to `MAX_ENTROPY`.
+    ///
+    /// An entropy of 0 is allowed: the timestamp, counter and process ID
+    /// alone keep the IDs unique, e.g. for ordered batches.
+    /// It is not checked against `max_total_len` until `build()`, see
+    /// `try_entropy` to fail as soon as it is set.
+    pub fn entropy(mut self, entropy: usize) -> Self {
+        *self.validated.get_mut() = false;
+        self.entropy = entropy;
+        self.entropy_max = None;
+        self
     }
-}
 
-/// Generates a base-36 encoded string from a `u128` value.
-fn to_base36(mut v: u128) -> String {
-    // 16 characters cover most cases which is typical for base-36 encoding of a u128
-    let mut result = String::with_capacity(16);
-    while v > 0 {
-        result.push(
-            char::from_digit(
-                u32::try_from(v % u128::from(BASE_36)).unwrap(),
-                u32::from(BASE_36),
-            )
-            .unwrap(),
-        );
-        v /= u128::from(BASE_36);
+    /// Picks the entropy of each ID at random within `[min, max]`, so IDs do
+    /// not reveal a fixed structure.
+    ///
+    /// IDs then vary in length, and their random sequence can only be told
+    /// apart from the fields by its position: enable `self_describing` when
+    /// consumers need the entropy of each ID. Returns
+    /// `PuidError::InvalidEntropyRange` if `min` exceeds `max`.
+    pub fn entropy_range(mut self, min: usize, max: usize) -> PuidResult<Self> {
+        *self.validated.get_mut() = false;
+        if min > max {
+            return Err(PuidError::InvalidEntropyRange);
+        }
+        self.entropy = min;
+        self.entropy_max = Some(max);
+        Ok(self)
     }
-    result.chars().rev().collect()
-}
 
-/// Generates a random alphanumeric string of the specified length.
-fn rnd_string(elements: u8) -> String {
-    thread_rng()
-        .sample_iter(&Alphanumeric)
-        .take(elements as usize)
-        .map(char::from)
-        .collect()
-}
+    /// Sets the entropy, failing with `PuidError::TooLong` if IDs would not
+    /// fit within `max_total_len`, with all the fields configured so far.
+    pub fn try_entropy(mut self, entropy: usize) -> PuidResult<Self> {
+        *self.validated.get_mut() = false;
+        self.entropy = entropy;
+        self.entropy_max = None;
+        if self.max_total_len.is_some_and(|max| self.max_chars() > max) {
+            return Err(PuidError::TooLong);
+        }
+        Ok(self)
+    }
 
-/// Increments and fetches an atomic counter, resetting to 0 upon reaching `u8::MAX`.
-fn counter() -> u8 {
-    COUNTER
-        .fetch_update(Ordering::SeqCst, Ordering::SeqCst, |i| match i {
-            i if i == u8::MAX => Some(0),
-            _ => Some(i + 1),
-        })
-        .unwrap()
-}
+    /// Sets the digits encoding the timestamp, counter and process ID, in
+    /// ascending order, instead of base-36.
+    ///
+    /// Returns `PuidError::EmptyAlphabet` if it is empty and
+    /// `PuidError::InvalidAlphabet` unless it has at least 2 unique characters.
+    pub fn numeric_alphabet(mut self, chars: &str) -> PuidResult<Self> {
+        *self.validated.get_mut() = false;
+        self.digits = Some(alphabet(chars)?);
+        self.readable = false;
+        Ok(self)
+    }
 
-/// Retrieves the current system time in milliseconds since the UNIX epoch.
-fn time() -> u128 {
-    SystemTime::now()
-        .duration_since(UNIX_EPOCH)
-        .unwrap()
-        .as_millis()
-}
+    /// Encodes the numeric fields in Crockford base-32, `0-9A-Z` without
+    /// `I`, `L`, `O` and `U`, instead of base-36, so IDs read aloud or retyped
+    /// are not misread.
+    ///
+    /// The timestamp takes one more character. Parsers also accept the
+    /// excluded letters, read as `1` and `0`, and lowercase digits. The
+    /// layout is recorded in the version marker, which stays in base-36, and
+    /// it replaces any `numeric_alphabet`.
+    pub fn crockford_base32(mut self, yes: bool) -> Self {
+        *self.validated.get_mut() = false;
+        if yes {
+            self.digits = Some(CROCKFORD.chars().collect());
+            self.readable = false;
+        } else if self.crockford {
+            self.digits = None;
+        }
+        self.crockford = yes;
+        self
+    }
 
-/// Validates the prefix for length and alphanumeric characters.
-fn validate(prefix: &str) -> bool {
-    (PREFIX_MIN_LEN..=PREFIX_MAX_LEN).contains(&prefix.len())
-        && prefix.chars().all(|c| c.is_ascii_alphanumeric())
-}
+    /// Sets the characters the random sequence is sampled from instead of
+    /// the alphanumeric ones.
+    ///
+    /// Returns `PuidError::EmptyAlphabet` if it is empty and
+    /// `PuidError::InvalidAlphabet` unless it has at least 2 unique characters.
+    pub fn tail_alphabet(mut self, chars: &str) -> PuidResult<Self> {
+        *self.validated.get_mut() = false;
+        self.alphabet = Some(alphabet(chars)?);
+        Ok(self)
+    }
 
-#[doc(hidden)]
-#[deprecated(since = "0.1.0", note = "Deprecated in favour of Puid::builder()")]
-#[allow(clippy::must_use_candidate)]
-// Composes the different parts of the ID.
-pub fn puid(pref: &str, elements: u8) -> String {
-    assert!(
-        validate(pref),
-        "Prefix cannot be longer than 4 characters and with non-alphanumeric characters."
-    );
+    /// Sets the characters the random sequence is sampled uniformly from,
+    /// e.g. `0123456789abcdefghijklmnopqrstuvwxyz` for IDs stored in case
+    /// insensitive paths. Same as `tail_alphabet`.
+    ///
+    /// Returns `PuidError::EmptyAlphabet` if it is empty and
+    /// `PuidError::InvalidAlphabet` unless it has at least 2 unique characters.
+    pub fn alphabet(self, chars: &str) -> PuidResult<Self> {
+        self.tail_alphabet(chars)
+    }
 
-    [
-        pref,
-        "_",
-        &to_base36(time()),
-        &counter().to_string(),
-        &to_base36(u128::from(std::process::id())),
-        &rnd_string(elements),
-    ]
-    .concat()
-}
+    /// Sets the letter case of the random sequence, e.g. `Case::Lower` for
+    /// IDs stored in case insensitive indexes.
+    ///
+    /// A single case keeps the digits, so each character carries about 5.17
+    /// bits instead of 5.95. It replaces any alphabet or character set.
+    pub fn case(mut self, case: Case) -> Self {
+        *self.validated.get_mut() = false;
+        let letters = match case {
+            Case::Lower => 'a'..='z',
+            Case::Upper => 'A'..='Z',
+            Case::Mixed => {
+                self.alphabet = None;
+                return self;
+            }
+        };
+        self.alphabet = Some(('0'..='9').chain(letters).collect());
+        self
+    }
 
-/// Abstract the ID generation for easy usage.
-///
-/// With default size of 12 random characters at the end.
-///
-/// ```rust
-/// puid::puid!("foo");
-/// ```
-///
-/// With custom size of 24 random characters at the end.
-///
-/// ```rust
-/// puid::puid!("bar", 24);
-/// ```
-#[macro_export]
-#[deprecated(since = "0.1.0", note = "Deprecated in favour of Puid::builder()")]
-macro_rules! puid {
-    // Default puid with size of 12 random characters at the end.
-    ($pref:expr) => {
-        $crate::puid($pref, 12)
-    };
+    /// Keeps the whole ID lowercase, e.g. for DNS labels or case folding
+    /// routers where mixed case IDs would collide.
+    ///
+    /// The random sequence defaults to lowercase letters and digits, as with
+    /// `Case::Lower`. `build()` returns `PuidError::InvalidPrefix` for a
+    /// prefix, `PuidError::InvalidSeparator` for a separator and
+    /// `PuidError::InvalidAlphabet` for an alphabet or digits with uppercase
+    /// characters, and `PuidError::ConflictingOptions` with an ISO timestamp.
+    pub fn lowercase(mut self, yes: bool) -> Self {
+        *self.validated.get_mut() = false;
+        self.lowercase = yes;
+        if yes && self.alphabet.is_none() {
+            self = self.case(Case::Lower);
+        }
+        self
+    }
 
-    // puid with custom size of random characters at the end.
-    ($pref:expr, $elements:expr) => {
-        $crate::puid($pref, $elements)
-    };
-}
+    /// Sets the character set of the random sequence.
+    ///
+    /// `CharSet::Base64Url` samples 6 bits per character from a single buffer
+    /// of random bytes, without bias since 64 divides 256. Its alphabet
+    /// includes `_`, so the separator becomes `.` unless set explicitly.
+    pub fn charset(mut self, set: CharSet) -> Self {
+        *self.validated.get_mut() = false;
+        match set {
+            CharSet::Alphanumeric => self.alphabet = None,
+            CharSet::Base64Url => {
+                self.alphabet = Some(BASE_64_URL.chars().collect());
+                self.bulk_random = true;
+                self.separator.get_or_insert(BASE_64_URL_SEPARATOR);
+            }
+        }
+        self
+    }
 
-#[cfg(test)]
-mod tests {
-    use super::*;
+    /// Sets the character separating the prefix from the body, `_` by default.
+    ///
+    /// `build()` returns `PuidError::InvalidSeparator` unless the separator is
+    /// a printable ASCII character, and `PuidError::SeparatorCollision` if it
+    /// can also appear in the prefix or in any encoded field.
+    pub fn separator(mut self, separator: char) -> Self {
+        *self.validated.get_mut() = false;
+        self.separator = Some(separator);
+        self
+    }
 
-    use std::collections::HashMap;
-    use std::thread;
+    /// Caps the total length of the generated ID in characters, see
+    /// `max_bytes` for its length in UTF-8 bytes.
+    ///
+    /// `build()` returns `PuidError::TooLong` when the ID does not fit.
+    pub fn max_total_len(mut self, max: usize) -> Self {
+        *self.validated.get_mut() = false;
+        self.max_total_len = Some(max);
+        self
+    }
 
-    #[test]
-    fn to_base36_test() {
-        assert_eq!(to_base36(1651312057), "rb5cjd");
+    /// Caps the UTF-8 byte length of the generated ID, e.g. for fixed-width
+    /// binary columns, which a tail alphabet with multi-byte characters can
+    /// exceed with fewer characters.
+    ///
+    /// Each ID is checked as generated, so `build()` returns
+    /// `PuidError::TooLong` only when that ID does not fit.
+    pub fn max_bytes(mut self, n: usize) -> Self {
+        *self.validated.get_mut() = false;
+        self.max_bytes = Some(n);
+        self
     }
 
-    #[test]
-    fn rnd_string_test() {
-        assert_eq!(rnd_string(12).len(), 12);
+    /// Configures human-friendly IDs for reading aloud or retyping.
+    ///
+    /// Every field after the separator is encoded with the lowercase alphabet
+    /// `23456789abcdefghijkmnpqrstuvwxyz`, which drops the ambiguous `0/O/1/l/I`,
+    /// and the random sequence is split in hyphen-separated groups of 4, e.g.
+    /// `foo_3pa823n6wni2ni22222222222wej-yevq-swut-eg9d`. The version marker
+    /// and flags in front are base-36 like every other ID, so `Puid::parse`
+    /// reads the fields back.
+    ///
+    /// Each random character carries 5 bits of entropy instead of the ~5.95
+    /// bits of the default alphanumeric sequence.
+    pub fn readable(mut self) -> Self {
+        *self.validated.get_mut() = false;
+        let alphabet: Vec<char> = READABLE_ALPHABET.chars().collect();
+        self.digits = Some(alphabet.clone());
+        self.alphabet = Some(alphabet);
+        self.group_len = Some(READABLE_GROUP_LEN);
+        self.crockford = false;
+        self.readable = true;
+        self
     }
 
-    #[test]
-    fn counter_test() {
-        let a = counter(); // 0
-        let b = counter();
-        let _ = thread::spawn(move || {
-            for _ in b + 1..=u8::MAX {
-                let _ = counter();
-            }
-        });
-        assert!(a + 1 == b);
-        assert_eq!(counter(), 2);
+    /// Configures IDs for printing and scanning with OCR.
+    ///
+    /// Every field after the version marker is encoded with the uppercase
+    /// alphabet `034679ACEFGHJKMNPRTUVWXY`, which drops the characters OCR
+    /// commonly confuses, e.g. `8/B`, `5/S`, `2/Z`, `0/O` and `1/I`, and the
+    /// ID ends in a Luhn mod 24 check character, verified by
+    /// `Puid::verify_check_char`, which detects any single substituted
+    /// character after the marker. The marker stays base-36 like every other
+    /// ID, but the fields cannot be parsed back, only checked.
+    ///
+    /// Each random character carries about 4.58 bits of entropy instead of
+    /// the ~5.95 bits of the default alphanumeric sequence, so 16 characters
+    /// are needed for the entropy of 12 default ones.
+    pub fn ocr_safe(mut self) -> Self {
+        *self.validated.get_mut() = false;
+        let alphabet: Vec<char> = OCR_ALPHABET.chars().collect();
+        self.digits = Some(alphabet.clone());
+        self.alphabet = Some(alphabet);
+        self.crockford = false;
+        self.readable = false;
+        self.ocr_safe = true;
+        self
     }
 
-    #[test]
-    fn validate_test() {
-        let tests = HashMap::from([
-            ("Valid prefix for 1 character long", ("f", true)),
-            ("Valid prefix for 2 character long", ("fo", true)),
-            ("Valid prefix for 3 character long", ("foo", true)),
-            ("Valid prefix for 4 character long", ("quux", true)),
-            ("Valid prefix for alphanumeric characters", ("b4r", true)),
-            (
-                "Invalid prefix for non-alphanumeric characters",
-                ("bäz", false),
-            ),
-            ("Invalid prefix with empty value", ("", false)),
-        ]);
-        for (desc, t) in tests {
-            assert_eq!(validate(t.0), t.1, "{desc}");
-        }
+    /// Configures IDs for the alphanumeric mode of QR codes, which encodes
+    /// `0-9A-Z $%*+-./:` at 5.5 bits per character instead of 8.
+    ///
+    /// The fields are encoded as uppercase base-36, the separator becomes
+    /// `-` unless set before, and the random sequence is drawn from the
+    /// other 44 characters of the set, so each carries about 5.46 bits of
+    /// entropy instead of ~5.95. `build()` returns `PuidError::InvalidPrefix`
+    /// for a prefix with lowercase letters, `PuidError::InvalidSeparator` for
+    /// a separator outside the set, `PuidError::InvalidAlphabet` for digits
+    /// or an alphabet outside it and `PuidError::ConflictingOptions` with a
+    /// locale or an opaque body.
+    ///
+    /// The IDs cannot be parsed back: the random sequence has characters
+    /// outside the parsed layout, so `Puid::validate_id` returns
+    /// `PuidError::Malformed`, and the uppercase fields read like base-62 to
+    /// `Puid::detect_format`.
+    pub fn qr_alphanumeric(mut self) -> Self {
+        *self.validated.get_mut() = false;
+        let separator = *self.separator.get_or_insert(QR_SEPARATOR);
+        self.digits = Some(QR_ALPHABET[..36].chars().collect());
+        self.crockford = false;
+        self.readable = false;
+        self.alphabet = Some(QR_ALPHABET.chars().filter(|&c| c != separator).collect());
+        self.qr_alphanumeric = true;
+        self
     }
 
-    #[test]
-    fn puid_builder_test() {
-        let id = Puid::builder().prefix("foo").unwrap().build();
-        assert!(id.is_ok());
+    /// Replaces the timestamp with a process-lifetime sequence.
+    ///
+    /// The field holds a global counter incremented per ID instead of the
+    /// wall-clock time, so IDs from one process sort in generation order even
+    /// if the clock jumps backwards. The creation time is not recoverable.
+    pub fn monotonic_only(mut self) -> Self {
+        *self.validated.get_mut() = false;
+        self.monotonic = true;
+        self
+    }
+
+    /// Appends the prefix length as a base-36 character at the end of the ID.
+    ///
+    /// The prefix can then be split off in constant time, without scanning
+    /// for the separator, by `Puid::prefix_of` and the parsers. The layout is
+    /// recorded in the version marker.
+    pub fn length_header(mut self, yes: bool) -> Self {
+        *self.validated.get_mut() = false;
+        self.length_header = yes;
+        self
+    }
+
+    /// Writes the timestamp as a basic ISO-8601 UTC time with milliseconds,
+    /// e.g. `20240612T101500000`, instead of base-36.
+    ///
+    /// The field is 9 characters longer but readable in logs, and is still
+    /// recovered by the parsers. The layout is recorded in the version marker.
+    /// `build()` returns `PuidError::ConflictingOptions` when scrambling too.
+    pub fn iso_timestamp(mut self, yes: bool) -> Self {
+        *self.validated.get_mut() = false;
+        self.iso_timestamp = yes;
+        self
+    }
+
+    /// Sets the resolution of the timestamp, milliseconds by default.
+    ///
+    /// Microseconds tell apart most IDs of a busy producer by their timestamp
+    /// alone, and seconds keep the field short. The timestamp field stays
+    /// fixed-width for the chosen resolution, so IDs still sort by time, and
+    /// any resolution other than milliseconds is recorded after the flags
+    /// of the version marker. ISO-8601 timestamps are always in milliseconds.
+    pub fn resolution(mut self, resolution: TimeResolution) -> Self {
+        *self.validated.get_mut() = false;
+        self.resolution = resolution;
+        self
+    }
+
+    /// Guarantees that IDs sort lexically in time order, e.g. as database
+    /// primary keys.
+    ///
+    /// The timestamp and counter are always zero-padded to a fixed width,
+    /// of timestamps up to the year 5188, so string comparison follows the
+    /// generation time and then the counter. This mode rejects the options
+    /// breaking that order: `build()` returns `PuidError::Unsortable` when
+    /// scrambling, hashing the body with `opaque`, encrypting the timestamp,
+    /// or encoding the fields with digits not in ascending character order.
+    pub fn sortable(mut self, yes: bool) -> Self {
+        *self.validated.get_mut() = false;
+        self.sortable = yes;
+        self
+    }
+
+    /// Repeats the separator between the fields for readability, e.g.
+    /// `foo_23k_0mv7m8kkc_0000000_0000aj8_EpBvUBU1dj8X`.
+    ///
+    /// Every field adds one separator to the length, 4 without optional
+    /// fields. The layout is recorded in the version marker.
+    pub fn delimited_fields(mut self, yes: bool) -> Self {
+        *self.validated.get_mut() = false;
+        self.delimited = yes;
+        self
+    }
+
+    /// Includes the process ID after the counter, the default.
+    ///
+    /// Leaving it out shortens every ID by 7 characters where it adds little
+    /// to uniqueness, e.g. in containers where it is almost always 1, and
+    /// avoids revealing it. The layout is recorded in the version marker.
+    pub fn include_pid(mut self, yes: bool) -> Self {
+        *self.validated.get_mut() = false;
+        self.no_pid = !yes;
+        self
+    }
+
+    /// Leaves the timestamp out, e.g. for purely random IDs whose creation
+    /// time is irrelevant and should not leak, relying on the counter and
+    /// random sequence for uniqueness.
+    ///
+    /// The layout is recorded in the version marker, so `Puid::timestamp_of`
+    /// returns `PuidError::Malformed` for these IDs. `build()` returns
+    /// `PuidError::Unsortable` for sortable IDs.
+    pub fn no_timestamp(mut self) -> Self {
+        *self.validated.get_mut() = false;
+        self.no_timestamp = true;
+        self
+    }
+
+    /// Includes an identifier of the generating thread after the process ID.
+    ///
+    /// Each thread then advances its own counter instead of contending on the
+    /// global one, and IDs stay unique across threads through the thread ID,
+    /// a base-36 field of 7 characters. The layout is recorded in the version
+    /// marker. Without `std` every thread is identified as 0 and shares the
+    /// global counter.
+    pub fn include_thread_id(mut self, yes: bool) -> Self {
+        *self.validated.get_mut() = false;
+        self.thread_id = yes;
+        self
+    }
+
+    /// Advances the counter by `step` on every ID instead of 1.
+    ///
+    /// Combined with `counter_start`, this partitions the counter space among
+    /// generators sharing it: `n` nodes using a step of `n` from the distinct
+    /// starts `0..n` never produce the same counter value. The counter wraps
+    /// modulo 2^32, so `step` should be a power of two for the partitions to
+    /// hold after wrapping. A step of 0 is treated as 1.
+    pub fn counter_step(mut self, step: u8) -> Self {
+        *self.validated.get_mut() = false;
+        self.counter_step = Some(step.max(1));
+        self
+    }
+
+    /// Uses a per-builder counter starting at `start` instead of the global one.
+    pub fn counter_start(mut self, start: u32) -> Self {
+        *self.validated.get_mut() = false;
+        self.counter = Some(AtomicU32::new(start));
+        self
+    }
+
+    /// Restarts the counter from 0 at every whole second of the timestamp,
+    /// so IDs carry a compact sequence within their second for systems
+    /// keyed on (second, sequence) pairs.
+    ///
+    /// The sequence is tracked per builder and takes precedence over the
+    /// other counters.
+    pub fn per_second_counter(mut self, yes: bool) -> Self {
+        *self.validated.get_mut() = false;
+        self.second_counter = yes.then(|| Mutex::new((u128::MAX, 0)));
+        self
+    }
+
+    /// Embeds a user-defined 16-bit value, e.g. an A/B bucket, so downstream
+    /// systems can route by it without a lookup. It is encoded as a base-36
+    /// field of 4 characters, readable with `Puid::tag_of`.
+    pub fn tag(mut self, value: u16) -> Self {
+        *self.validated.get_mut() = false;
+        self.tag = Some(value);
+        self
+    }
+
+    /// Separates the IDs of otherwise identical deployments by a namespace.
+    ///
+    /// A 32-bit FNV-1a hash of `ns` is placed into the ID as a base-36 field
+    /// of 7 characters and mixed into the seed of a seeded generator,
+    /// whether seeded before or after, so deployments with different
+    /// namespaces do not produce identical IDs even with the same clock and
+    /// counter. Distinct namespaces may still share a hash, with a
+    /// probability of about 1 in 4 billion per pair.
+    pub fn namespace(mut self, ns: &str) -> Self {
+        *self.validated.get_mut() = false;
+        self.namespace = Some(fnv1a(ns));
+        if let Some(seed) = self.seed {
+            self.reseed(seed);
+        }
+        self
+    }
+
+    /// Embeds a stable machine identifier, e.g. a host or replica number, so
+    /// IDs from different nodes stay apart. It is encoded as a base-36 field
+    /// of 4 characters after the process ID, and omitted when unset.
+    pub fn node_id(mut self, id: u16) -> Self {
+        *self.validated.get_mut() = false;
+        self.node_id = Some(id);
+        self
+    }
+
+    /// Embeds a stable identifier of a client installation, e.g. generated
+    /// on first launch of an app, so IDs created client-side trace back to
+    /// it without a server round-trip, see `Puid::installation_of`.
+    ///
+    /// Unlike `node_id`, which tells servers apart, it is meant for client
+    /// devices. It is encoded as a base-36 field of 13 characters after the
+    /// node ID, and omitted when unset.
+    pub fn installation_id(mut self, id: u64) -> Self {
+        *self.validated.get_mut() = false;
+        self.installation_id = Some(id);
+        self
+    }
+
+    /// Embeds an expiry timestamp, the creation time plus `ttl`, so stateless
+    /// services can reject stale IDs with `Puid::is_expired`.
+    ///
+    /// The expiry is encoded in milliseconds as a base-36 field of 9
+    /// characters after the node ID. It is not authenticated: sign the IDs
+    /// when clients must not extend it.
+    pub fn expires_in(mut self, ttl: Duration) -> Self {
+        *self.validated.get_mut() = false;
+        self.ttl = Some(ttl);
+        self
+    }
+
+    /// Replaces the body with a 25-character base-36 hash of all its
+    /// components, the timestamp, counter, process ID and random sequence,
+    /// so IDs expose no structure to enumerate or infer from.
+    ///
+    /// The fields are not recoverable: parsing, timestamp extraction and the
+    /// other inspections of the layout do not work on opaque IDs, which also
+    /// no longer sort by time. Scrambling does not apply.
+    pub fn opaque(mut self, yes: bool) -> Self {
+        *self.validated.get_mut() = false;
+        self.opaque = yes;
+        self
+    }
+
+    /// Records the entropy right after the version marker, as 2 base-36
+    /// characters, so parsers know the length of the random sequence without
+    /// sharing the builder configuration. Entropies above 255 do not fit and
+    /// fail with `PuidError::EntropyOutOfRange`.
+    pub fn self_describing(mut self, yes: bool) -> Self {
+        *self.validated.get_mut() = false;
+        self.self_describing = yes;
+        self
+    }
+
+    /// Aligns the body to a multiple of 4 characters, extending the random
+    /// sequence as needed, so it decodes as unpadded base64url into a
+    /// well-formed byte sequence, see `Puid::decode_body`.
+    ///
+    /// The body must only use base64url characters, which holds for the
+    /// default alphabets. Opaque IDs are not aligned.
+    pub fn decodable(mut self, yes: bool) -> Self {
+        *self.validated.get_mut() = false;
+        self.decodable = yes;
+        self
+    }
+
+    /// Scrambles the fields with a reversible per-position shift keyed by the
+    /// random sequence, so sequential IDs do not look similar.
+    ///
+    /// Scrambled IDs no longer sort by time; `Puid::unscramble` recovers the
+    /// ordered form. The fields must be base-36, so building fails with
+    /// `PuidError::InvalidAlphabet` when combined with a numeric alphabet.
+    pub fn scramble(mut self, yes: bool) -> Self {
+        *self.validated.get_mut() = false;
+        self.scramble = yes;
+        self
+    }
+
+    /// Encrypts the timestamp with a format-preserving cipher keyed by `key`,
+    /// so IDs sort by time for holders of the key, with
+    /// `Puid::decrypt_timestamp`, and look unordered to everyone else.
+    ///
+    /// The timestamp keeps its width and encoding and the IDs carry no mark
+    /// of the encryption, so `Puid::timestamp_of` reads a meaningless time.
+    /// The cipher is an 8-round Feistel network over FNV-1a keyed with a
+    /// 64-bit hash of `key`: it hides the creation order from observers but
+    /// is no vetted cipher. Building fails with `PuidError::Unsortable` when
+    /// sortable and `PuidError::ConflictingOptions` for ISO-8601 timestamps.
+    pub fn encrypted_sortable(mut self, key: &[u8]) -> Self {
+        *self.validated.get_mut() = false;
+        self.time_key = Some(fpe::derive_key(key));
+        self
+    }
+
+    /// Fills the random sequence from a single buffer of random bytes mapped
+    /// onto the alphabet instead of sampling every character, which is faster
+    /// for large entropies. The mapping rejects out-of-range bytes, so the
+    /// characters remain uniformly distributed. Alphabets of more than 256
+    /// characters keep sampling every character.
+    pub fn bulk_random(mut self, yes: bool) -> Self {
+        *self.validated.get_mut() = false;
+        self.bulk_random = yes;
+        self
+    }
+
+    /// Reads the timestamps from `clock` instead of the system clock, e.g. a
+    /// fixed or manually advanced one for predictable IDs in tests.
+    ///
+    /// Clocks have millisecond precision, so microsecond timestamps end in
+    /// `000`. A fixed time, as set by `deterministic`, takes precedence.
+    pub fn clock(mut self, clock: impl Clock + 'a) -> Self {
+        *self.validated.get_mut() = false;
+        self.clock = Some(Box::new(clock));
+        self
+    }
+
+    /// Sets a transform applied to the final string of every generated ID,
+    /// including the ones yielded by iterating over the builder.
+    ///
+    /// Transforms that alter the layout, e.g. uppercasing the base-36 fields,
+    /// produce IDs that can no longer be parsed.
+    pub fn transform(mut self, f: impl Fn(String) -> String + Send + Sync + 'a) -> Self {
+        *self.validated.get_mut() = false;
+        self.transform = Some(Box::new(f));
+        self
+    }
+
+    /// Remembers every generated ID and retries any duplicate.
+    ///
+    /// The remembered set grows with every ID, so this suits bounded batches
+    /// rather than long-running generators.
+    pub fn dedup(mut self, yes: bool) -> Self {
+        *self.validated.get_mut() = false;
+        self.seen = yes.then(Mutex::default);
+        self
+    }
+
+    /// Hints the expected number of IDs per batch, so the remembered set of
+    /// `dedup` grows by whole batches instead of doubling repeatedly. The
+    /// generated IDs are unchanged.
+    pub fn reserve(mut self, n: usize) -> Self {
+        *self.validated.get_mut() = false;
+        self.reserve = Some(n);
+        self
+    }
+
+    /// Sets how a duplicate ID is retried under `dedup`, trading length or
+    /// time accuracy for uniqueness. Gives up with `PuidError::Duplicate`
+    /// after 16 retries.
+    pub fn dedup_strategy(mut self, strategy: RetryStrategy) -> Self {
+        *self.validated.get_mut() = false;
+        self.strategy = strategy;
+        self
+    }
+
+    /// Sets a constant ID returned by `build_cow` instead of generating one,
+    /// e.g. a fixed value in tests.
+    pub fn placeholder(mut self, id: &'static str) -> Self {
+        *self.validated.get_mut() = false;
+        self.placeholder = Some(id);
+        self
+    }
+
+    /// Samples the random sequence from a generator seeded with `seed`
+    /// instead of `thread_rng()`, so builders with the same seed produce the
+    /// same random sequences. The clock and counter still advance.
+    pub fn seed(mut self, seed: u64) -> Self {
+        *self.validated.get_mut() = false;
+        self.reseed(seed);
+        self
+    }
+
+    /// Installs a generator seeded with `seed` mixed with the namespace hash.
+    fn reseed(&mut self, seed: u64) {
+        self.seed = Some(seed);
+        let seed = seed ^ u64::from(self.namespace.unwrap_or(0));
+        self.rng = Some(Mutex::new(StdRng::seed_from_u64(seed)));
+    }
+
+    /// Makes the builder fully deterministic for testing.
+    ///
+    /// Installs a fixed clock, a per-builder counter starting at 0 and a
+    /// random generator seeded with `seed`, so builders with the same seed
+    /// produce byte-identical IDs. The process ID is pinned too, so the IDs
+    /// are identical across processes.
+    #[cfg(feature = "testing")]
+    pub fn deterministic(mut self, seed: u64) -> Self {
+        *self.validated.get_mut() = false;
+        self.time = Some(DETERMINISTIC_TIME);
+        self.pid = Some(DETERMINISTIC_PID);
+        self.counter = Some(AtomicU32::new(0));
+        self.reseed(seed);
+        self
+    }
+
+    /// Runs the configuration checks of `build()` without generating an ID,
+    /// e.g. to fail fast at startup before a long batch run.
+    ///
+    /// Returns `PuidError::InvalidPrefix` for a missing, too short or too
+    /// long prefix,
+    /// `PuidError::InvalidSeparator` for a non-printable or non-ASCII
+    /// separator, `PuidError::SeparatorCollision` for a colliding one,
+    /// `PuidError::Unsortable` for a sortable configuration breaking the order,
+    /// `PuidError::EntropyOutOfRange` above `MAX_ENTROPY`,
+    /// `PuidError::InvalidAlphabet` for scrambling numeric fields,
+    /// `PuidError::ConflictingOptions` for options which cannot be combined,
+    /// `PuidError::MissingSource` for a missing clock or seed without `std`
+    /// and `PuidError::TooLong` if IDs may exceed the maximum length.
+    pub fn validate(&self) -> PuidResult<()> {
+        // Without `std` there is no system clock or `thread_rng()` to fall back to
+        if cfg!(not(feature = "std"))
+            && (self.rng.is_none() || (self.time.is_none() && self.clock.is_none()))
+        {
+            return Err(PuidError::MissingSource);
+        }
+        if self.separator_char().is_some() {
+            if self.prefix_out_of_bounds() {
+                return Err(PuidError::InvalidPrefix);
+            }
+            self.check_separator()?;
+        } else if self.locale.is_some() {
+            return Err(PuidError::InvalidLocale);
+        }
+        self.check_sortable()?;
+        self.check_lowercase()?;
+        self.check_qr_alphanumeric()?;
+        let entropy_limit = if self.self_describing {
+            usize::from(u8::MAX)
+        } else {
+            MAX_ENTROPY
+        };
+        if self.max_entropy() > entropy_limit {
+            return Err(PuidError::EntropyOutOfRange);
+        }
+        if self.scramble && !self.opaque && self.digits.is_some() {
+            return Err(PuidError::InvalidAlphabet);
+        }
+        if self.scramble && !self.opaque && self.iso_timestamp {
+            return Err(PuidError::ConflictingOptions);
+        }
+        self.check_encrypted()?;
+        // Flags past the wide layout have no version marker to carry them
+        if u128::from(self.flags()) > WIDE_FLAGS_MAX {
+            return Err(PuidError::ConflictingOptions);
+        }
+        if self.max_total_len.is_some_and(|max| self.max_chars() > max) {
+            return Err(PuidError::TooLong);
+        }
+        Ok(())
+    }
+
+    /// Builds the final PUID string, validating the whole configuration
+    /// first like `validate` so the most specific error is returned.
+    pub fn build(self) -> PuidResult<String> {
+        self.validate()?;
+        self.generate()
+    }
+
+    /// Builds the PUID as a typed `PuidId` instead of a string.
+    ///
+    /// Returns `PuidError::Malformed` if the ID does not keep the parseable
+    /// layout, e.g. when hashed with `opaque` or rewritten by a transform.
+    pub fn build_typed(self) -> PuidResult<PuidId> {
+        self.build()?.parse()
+    }
+
+    /// Builds the PUID as a `Cow`, borrowing the placeholder if one is set and
+    /// owning a freshly generated ID otherwise.
+    pub fn build_cow(self) -> PuidResult<Cow<'static, str>> {
+        if let Some(id) = self.placeholder {
+            return Ok(Cow::Borrowed(id));
+        }
+        self.validate()?;
+        self.generate().map(Cow::Owned)
+    }
+
+    /// Validates the configuration once and returns an iterator of IDs, e.g.
+    /// `builder.iter()?.take(1000).collect::<Vec<_>>()` for fixtures.
+    ///
+    /// The iterator ends early if generating an ID fails after validation,
+    /// e.g. on a duplicate under `RetryStrategy::Fail`.
+    pub fn iter(self) -> PuidResult<PuidIntoIter<'a>> {
+        self.validate()?;
+        Ok(PuidIntoIter { builder: self })
+    }
+
+    /// Generates as many IDs as possible before `deadline`, e.g. to fill a
+    /// buffer in idle time.
+    ///
+    /// The configuration is validated once, and the result is empty if the
+    /// deadline has already passed.
+    #[cfg(feature = "std")]
+    pub fn gen_until(&self, deadline: Instant) -> PuidResult<Vec<String>> {
+        self.gen_until_with(deadline, Instant::now)
+    }
+
+    /// Same as `gen_until`, reading the current instant from `now`.
+    #[cfg(feature = "std")]
+    fn gen_until_with(
+        &self,
+        deadline: Instant,
+        mut now: impl FnMut() -> Instant,
+    ) -> PuidResult<Vec<String>> {
+        self.validate()?;
+        let mut ids = Vec::new();
+        while now() < deadline {
+            ids.push(self.generate()?);
+        }
+        Ok(ids)
+    }
+
+    /// Builds the PUID with the given timestamp in milliseconds since the
+    /// UNIX epoch instead of the current time, e.g. to backfill records.
+    pub fn build_with_time(self, ms: u128) -> PuidResult<String> {
+        self.validate()?;
+        self.generate_pinned(Pins {
+            time: Some(ms),
+            ..Pins::default()
+        })
+    }
+
+    /// Builds the PUID with the given counter value instead of advancing a
+    /// counter, leaving the global one untouched. Combined with
+    /// `build_with_time` and a seeded generator, IDs are fully reproducible.
+    pub fn build_with_counter(self, value: u32) -> PuidResult<String> {
+        self.validate()?;
+        self.generate_pinned(Pins {
+            counter: Some(value),
+            ..Pins::default()
+        })
+    }
+
+    /// Builds the PUID into a stack-allocated `ArrayString` of capacity `N`.
+    ///
+    /// Returns `PuidError::TooLong` if the ID does not fit in `N` bytes.
+    #[cfg(feature = "arrayvec")]
+    pub fn build_inline<const N: usize>(&self) -> PuidResult<arrayvec::ArrayString<N>> {
+        self.validate()?;
+        if self.rewrites() {
+            return arrayvec::ArrayString::from(&self.generate()?).map_err(|_| PuidError::TooLong);
+        }
+        let mut result = arrayvec::ArrayString::new();
+        self.write_id(&mut result, Pins::default())?;
+        Ok(result)
+    }
+
+    /// Builds a KSUID-compatible ID: the prefix and separator followed by a
+    /// 27-character base-62 body.
+    ///
+    /// The body encodes 20 bytes, a 32-bit timestamp in seconds since the
+    /// KSUID epoch followed by a 128-bit random payload, so IDs sort by time
+    /// at second resolution. The entropy and alphabets do not apply.
+    pub fn ksuid_compat(self) -> PuidResult<String> {
+        self.validate()?;
+
+        let seconds = (self.time() / 1000).saturating_sub(KSUID_EPOCH);
+        let mut bytes = [0u8; 20];
+        bytes[..4].copy_from_slice(&u32::try_from(seconds).unwrap_or(u32::MAX).to_be_bytes());
+        self.fill_bytes(&mut bytes[4..]);
+
+        let mut result = String::with_capacity(self.prefix.len() + 4 + KSUID_LEN);
+        result.push_str(&self.prefix);
+        result.push(self.separator.unwrap_or(DEFAULT_SEPARATOR));
+        result.push_str(&to_base62(&bytes, KSUID_LEN));
+        Ok(self.finish(result))
+    }
+
+    /// Builds an ID made of digits only, for systems accepting numeric strings.
+    ///
+    /// The fields are encoded in base-10 and the random sequence is sampled
+    /// from digits, while the separator is omitted. The prefix must therefore
+    /// be numeric or omitted, e.g. `Puid::builder().numeric_only()`, and
+    /// `PuidError::InvalidPrefix` is returned otherwise.
+    pub fn numeric_only(mut self) -> PuidResult<String> {
+        if !self.prefix.chars().all(|c| c.is_ascii_digit()) {
+            return Err(PuidError::InvalidPrefix);
+        }
+        let decimal: Vec<char> = DECIMAL.chars().collect();
+        self.digits = Some(decimal.clone());
+        self.alphabet = Some(decimal);
+        self.group_len = None;
+        self.numeric = true;
+        self.validate()?;
+        self.generate()
+    }
+
+    /// Builds `n` IDs that are unique and strictly increasing in one pass,
+    /// e.g. for a bulk event log.
+    ///
+    /// The batch keeps its own logical clock in ticks of the resolution: the
+    /// counter restarts whenever the clock moves forward and the time
+    /// advances by a tick whenever the counter would wrap, so the order holds even under a
+    /// frozen clock. When deduplicating, the IDs are also checked against
+    /// those generated before, failing with `PuidError::Duplicate`.
+    pub fn build_ordered_unique(&self, n: usize) -> PuidResult<Vec<String>> {
+        self.validate()?;
+        let mut ids = Vec::with_capacity(n);
+        #[cfg(feature = "std")]
+        if let Some(seen) = &self.seen {
+            seen.lock().unwrap().reserve(n);
+        }
+        let resolution = self.time_resolution();
+        let mut last: Option<(u128, u32)> = None;
+        for _ in 0..n {
+            let now = self.now_ticks(resolution);
+            let (time, counter) = match last {
+                Some((time, u32::MAX)) if now <= time => (time + 1, 0),
+                Some((time, counter)) if now <= time => (time, counter + 1),
+                _ => (now, 0),
+            };
+            last = Some((time, counter));
+
+            let pins = Pins {
+                ticks: Some(time),
+                counter: Some(counter),
+                ..Pins::default()
+            };
+            let id = self.finish(self.compose_pinned(pins)?.0);
+            if let Some(seen) = &self.seen {
+                if !seen.lock().unwrap().insert(id.clone()) {
+                    return Err(PuidError::Duplicate);
+                }
+            }
+            ids.push(id);
+        }
+        Ok(ids)
+    }
+
+    /// Builds the PUID along with a descending sort key, identical except for
+    /// the timestamp whose digits are complemented, so keys in ascending
+    /// lexical order list the newest IDs first, e.g. for most-recent-first
+    /// index scans.
+    ///
+    /// Returns `PuidError::Unsortable` when the timestamp is not written as
+    /// plain digits to complement: hashed with `opaque`, scrambled,
+    /// encrypted, in ISO-8601 form or left out.
+    pub fn build_with_desc_key(self) -> PuidResult<(String, String)> {
+        self.validate()?;
+        if self.opaque
+            || self.scramble
+            || self.time_key.is_some()
+            || self.iso_timestamp
+            || self.no_timestamp
+        {
+            return Err(PuidError::Unsortable);
+        }
+        let (id, visible) = self.compose()?;
+        let start = visible - self.time_len();
+        let digits: Vec<char> = match &self.digits {
+            Some(digits) => digits.clone(),
+            None => (0..u32::from(BASE_36))
+                .map(|d| char::from_digit(d, u32::from(BASE_36)).unwrap())
+                .collect(),
+        };
+        let complement: String = id[start..visible]
+            .chars()
+            .map(|c| match digits.iter().position(|&digit| digit == c) {
+                Some(d) => digits[digits.len() - 1 - d],
+                None => c,
+            })
+            .collect();
+        let key = [&id[..start], &complement, &id[visible..]].concat();
+        Ok((self.finish(id), self.finish(key)))
+    }
+
+    /// Builds the final PUID string along with a redacted form for logging.
+    ///
+    /// The redacted form keeps the prefix, version and timestamp and masks the
+    /// rest with `****`, e.g. `("foo_10mv7m8kkc00000000000aj8OE3q1tEDrfSC", "foo_10mv7m8kkc****")`.
+    pub fn build_redacted(self) -> PuidResult<(String, String)> {
+        self.validate()?;
+        let (id, visible) = self.compose()?;
+        let redacted = [&id[..visible], "****"].concat();
+        Ok((self.finish(id), self.finish(redacted)))
+    }
+
+    /// Builds the final PUID string along with the random bytes its random
+    /// sequence encodes, e.g. to derive a secret from the same draw.
+    ///
+    /// Instead of sampling each character, `floor(entropy * log2(alphabet
+    /// size) / 8)` bytes are drawn and written as a big-endian number in the
+    /// alphabet, padded to `entropy` characters. The bytes are uniform and
+    /// carry 8 bits each, while the sequence keeps at most that many bits, so
+    /// 12 alphanumeric characters carry 8 bytes and 16 of
+    /// `CharSet::Base64Url` exactly 12.
+    pub fn build_with_random_bytes(self) -> PuidResult<(String, Vec<u8>)> {
+        self.validate()?;
+        let bytes = RefCell::new(Vec::new());
+        let id = self.generate_pinned(Pins {
+            bytes: Some(&bytes),
+            ..Pins::default()
+        })?;
+        Ok((id, bytes.into_inner()))
+    }
+
+    /// Builds an opaque public ID to expose, along with a sequential internal
+    /// ID to store, e.g. as a primary key keeping inserts ordered while the
+    /// public ID resists enumeration.
+    ///
+    /// The internal IDs start at 1 and strictly increase across all
+    /// builders of the process. Both forms come from one draw: the public ID
+    /// hashes the time read once and the low 32 bits of the internal ID as
+    /// its counter. The configuration is validated before drawing, so only a
+    /// failure to generate, e.g. a duplicate, skips an internal ID.
+    #[allow(clippy::cast_possible_truncation)]
+    pub fn dual(self) -> PuidResult<(String, u64)> {
+        let builder = self.opaque(true);
+        builder.validate()?;
+        let internal = INTERNAL_ID.fetch_add(1, Ordering::SeqCst);
+        let id = builder.generate_pinned(Pins {
+            ticks: Some(builder.now_ticks(builder.time_resolution())),
+            counter: Some(internal as u32),
+            ..Pins::default()
+        })?;
+        Ok((id, internal))
+    }
+
+    /// Generates an ID without consuming the builder.
+    pub(crate) fn generate(&self) -> PuidResult<String> {
+        self.generate_pinned(Pins::default())
+    }
+
+    /// Generates an ID not in `existing`, generating again on a collision.
+    #[cfg(feature = "std")]
+    fn generate_avoiding(&self, existing: &HashSet<String>) -> PuidResult<String> {
+        self.validate()?;
+        for _ in 0..=MAX_DEDUP_RETRIES {
+            let id = self.generate()?;
+            if !existing.contains(&id) {
+                return Ok(id);
+            }
+        }
+        Err(PuidError::Duplicate)
+    }
+
+    /// Generates an ID with the given pinned values, retrying duplicates.
+    fn generate_pinned(&self, pins: Pins<'_>) -> PuidResult<String> {
+        let Some(seen) = &self.seen else {
+            return self.compose_pinned(pins).map(|(id, _)| self.finish(id));
+        };
+
+        for retry in 0..=MAX_DEDUP_RETRIES {
+            let pins = Pins { retry, ..pins };
+            let id = self.finish(self.compose_pinned(pins)?.0);
+            let mut seen = seen.lock().unwrap();
+            #[cfg(feature = "std")]
+            if let Some(n) = self.reserve.filter(|_| seen.len() == seen.capacity()) {
+                seen.reserve(n);
+            }
+            if seen.insert(id.clone()) {
+                return Ok(id);
+            }
+            if self.strategy == RetryStrategy::Fail {
+                break;
+            }
+        }
+        Err(PuidError::Duplicate)
+    }
+
+    /// Builds the PUID into `buf`, clearing it first, so a buffer reused
+    /// across calls saves the allocation of each ID.
+    ///
+    /// The configuration is validated on the first call only, until an
+    /// option is set again. Transforms, deduplication, scrambling and opaque
+    /// IDs still go through an intermediate string.
+    pub fn build_into(&self, buf: &mut String) -> PuidResult<()> {
+        self.generate_into(buf)
+    }
+
+    /// Builds `n` IDs along with their components, recorded while writing
+    /// them, e.g. for bulk inserts indexing both the string and the
+    /// timestamp without parsing every ID again.
+    ///
+    /// The components are those written before any transform, scrambling or
+    /// hashing with `opaque`, so the timestamp of an encrypted one is the
+    /// plain time. Duplicates are retried like with `build()`.
+    pub fn build_many_with_parts(&self, n: usize) -> PuidResult<Vec<(String, PuidParts)>> {
+        self.validate()?;
+        let parts = RefCell::new(None);
+        let pins = Pins {
+            parts: Some(&parts),
+            ..Pins::default()
+        };
+        (0..n)
+            .map(|_| {
+                let id = self.generate_pinned(pins)?;
+                Ok((id, parts.take().unwrap()))
+            })
+            .collect()
+    }
+
+    /// Generates an ID into `out`, reusing its allocation unless a transform
+    /// or deduplication requires an intermediate string.
+    pub(crate) fn generate_into(&self, out: &mut String) -> PuidResult<()> {
+        if !self.validated.load(Ordering::Relaxed) {
+            self.validate()?;
+            self.validated.store(true, Ordering::Relaxed);
+        }
+        out.clear();
+        out.reserve(self.capacity());
+        if self.rewrites() {
+            out.push_str(&self.generate()?);
+            return Ok(());
+        }
+        self.write_id(out, Pins::default()).map(|_| ())
+    }
+
+    /// Returns whether the written ID is rewritten or checked afterwards,
+    /// requiring an intermediate string.
+    fn rewrites(&self) -> bool {
+        self.transform.is_some() || self.seen.is_some() || self.scramble || self.opaque
+    }
+
+    /// Applies the transform, if any, to a generated string.
+    pub(crate) fn finish(&self, id: String) -> String {
+        match &self.transform {
+            Some(f) => f(id),
+            None => id,
+        }
+    }
+
+    /// Composes the ID, returning it along with the length of its prefix,
+    /// separator and timestamp.
+    pub(crate) fn compose(&self) -> PuidResult<(String, usize)> {
+        self.compose_pinned(Pins::default())
+    }
+
+    /// Returns the capacity reserved for an ID before writing it, sized for
+    /// the default layout without allocating. Unlike `max_len`, it does not
+    /// count the locale, flags and optional fields, the padding of decodable
+    /// IDs or the characters added by retries, so the string may still grow.
+    fn capacity(&self) -> usize {
+        // self.prefix.len() for the prefix,
+        // 4 for the separator in UTF-8
+        // 1 for the version
+        // 16 for the time value in base-36 (which is a reasonable upper bound)
+        // 10 for the counter value, at the fixed width of `u32::MAX` in decimal
+        // 16 for the process ID in base-36, unless omitted
+        // the largest entropy for the random alphanumeric string
+        // the largest entropy for the group hyphens or multi-byte characters
+        let pid_len = if self.no_pid { 0 } else { 16 };
+        self.prefix.len() + 4 + 1 + 16 + 10 + pid_len + self.max_entropy() * 2
+    }
+
+    /// Composes the ID with the given pinned values.
+    fn compose_pinned(&self, pins: Pins<'_>) -> PuidResult<(String, usize)> {
+        let mut result = String::with_capacity(self.capacity());
+        let mut visible = self.write_id(&mut result, pins)?;
+        if self.opaque {
+            visible = self.head_len() + self.locale_len();
+            result = opaque(&result, visible);
+        } else if self.scramble {
+            result = scramble(&result)?;
+        }
+        Ok((result, visible))
+    }
+
+    /// Writes the ID into `out` with the given pinned values, mutated
+    /// according to the retry strategy, returning the length of its prefix,
+    /// separator and timestamp.
+    ///
+    /// The configuration must have passed `validate` first, the options are
+    /// not checked again for each ID.
+    fn write_id<S: Sink>(&self, out: &mut S, pins: Pins<'_>) -> PuidResult<usize> {
+        out.push_str(&self.prefix)?;
+        if let Some(separator) = self.separator_char() {
+            out.push(separator)?;
+            if let Some(locale) = &self.locale {
+                out.push_str(locale)?;
+                out.push(separator)?;
+            }
+        }
+        let resolution = self.time_resolution();
+        let mut time = match (pins.ticks, pins.time) {
+            (Some(ticks), _) => ticks,
+            (None, Some(time)) => resolution.ticks(time),
+            (None, None) => self.now_ticks(resolution),
+        };
+        let mut entropy = match self.entropy_max {
+            Some(max) => self.with_rng(|rng| rng.gen_range(self.entropy..=max)),
+            None => self.entropy,
+        };
+        match self.strategy {
+            RetryStrategy::AdvanceTime => time += u128::from(pins.retry),
+            RetryStrategy::BumpEntropy => entropy = entropy.saturating_add(usize::from(pins.retry)),
+            RetryStrategy::BumpCounter | RetryStrategy::Fail => {}
+        }
+        let entropy = self.padded_entropy(entropy);
+        let flags = self.flags();
+        if flags == 0 {
+            self.marker_into(out, u128::from(VERSION), 1)?;
+        } else if u128::from(flags) > FLAGS_MAX {
+            self.marker_into(out, u128::from(WIDE_VERSION), 1)?;
+            self.marker_into(out, u128::from(flags), WIDE_FLAGS_MAX)?;
+        } else {
+            self.marker_into(out, u128::from(EXTENDED_VERSION), 1)?;
+            self.marker_into(out, u128::from(flags), FLAGS_MAX)?;
+        }
+        if self.self_describing {
+            self.delimit(out)?;
+            self.encode_into(out, entropy as u128, u128::from(u8::MAX))?;
+        }
+        if resolution != TimeResolution::Millis {
+            self.delimit(out)?;
+            self.encode_into(out, resolution.code(), RESOLUTION_MAX)?;
+        }
+        let counter = pins
+            .counter
+            .unwrap_or_else(|| self.counter(resolution.millis(time)));
+        if pins.time.is_none()
+            && pins.ticks.is_none()
+            && !self.monotonic
+            && self.time.is_none()
+            && self.clock.is_none()
+        {
+            let wrapped = pins.counter.is_none()
+                && counter
+                    .checked_add(u32::from(self.counter_step.unwrap_or(1)))
+                    .is_none();
+            time = wrap_floor(resolution, time, wrapped);
+        }
+        if !self.no_timestamp {
+            self.delimit(out)?;
+            if self.iso_timestamp {
+                out.push_str(&iso::format(time))?;
+            } else if let Some(key) = self.time_key.filter(|_| time < self.time_domain()) {
+                let domain = self.time_domain();
+                self.encode_into(out, fpe::encrypt(key, time, domain), domain - 1)?;
+            } else {
+                self.encode_into(out, time, resolution.max())?;
+            }
+        }
+        let visible = out.len();
+        self.delimit(out)?;
+        self.encode_into(out, u128::from(counter), u128::from(u32::MAX))?;
+        if !self.no_pid {
+            self.delimit(out)?;
+            self.encode_into(out, u128::from(self.pid()), u128::from(u32::MAX))?;
+        }
+        if self.thread_id {
+            self.delimit(out)?;
+            self.encode_into(out, u128::from(thread_id()), u128::from(u32::MAX))?;
+        }
+        if let Some(tag) = self.tag {
+            self.delimit(out)?;
+            self.encode_into(out, u128::from(tag), u128::from(u16::MAX))?;
+        }
+        if let Some(namespace) = self.namespace {
+            self.delimit(out)?;
+            self.encode_into(out, u128::from(namespace), u128::from(u32::MAX))?;
+        }
+        if let Some(node_id) = self.node_id {
+            self.delimit(out)?;
+            self.encode_into(out, u128::from(node_id), u128::from(u16::MAX))?;
+        }
+        if let Some(installation_id) = self.installation_id {
+            self.delimit(out)?;
+            self.encode_into(out, u128::from(installation_id), u128::from(u64::MAX))?;
+        }
+        if let Some(ttl) = self.ttl {
+            self.delimit(out)?;
+            let created = pins
+                .time
+                .or_else(|| pins.ticks.map(|ticks| resolution.millis(ticks)))
+                .unwrap_or_else(|| self.time());
+            let expiry = created.saturating_add(ttl.as_millis()).min(TIME_MAX);
+            self.encode_into(out, expiry, TIME_MAX)?;
+        }
+        if entropy > MAX_ENTROPY {
+            return Err(PuidError::EntropyTooLarge);
+        }
+        self.delimit(out)?;
+        let random = out.len();
+        let mut buf = [0u8; STACK_ENTROPY];
+        let owned;
+        let tail = match pins.bytes {
+            Some(bytes) => {
+                owned = self.rnd_encoded(entropy, &mut bytes.borrow_mut());
+                owned.as_str()
+            }
+            None => match buf.get_mut(..entropy).and_then(|buf| self.rnd_ascii(buf)) {
+                Some(tail) => tail,
+                None => {
+                    owned = self.rnd_string(entropy);
+                    owned.as_str()
+                }
+            },
+        };
+        match self.group_len {
+            Some(len) => {
+                for (i, c) in tail.chars().enumerate() {
+                    if i % len == 0 {
+                        out.push('-')?;
+                    }
+                    out.push(c)?;
+                }
+            }
+            None => out.push_str(tail)?,
+        }
+        if let Some(parts) = pins.parts {
+            let version = if flags == 0 {
+                VERSION
+            } else if u128::from(flags) > FLAGS_MAX {
+                WIDE_VERSION
+            } else {
+                EXTENDED_VERSION
+            };
+            *parts.borrow_mut() = Some(PuidParts {
+                prefix: self.prefix.to_string(),
+                version: Some(version),
+                timestamp_ms: (!self.no_timestamp).then(|| resolution.millis(time)),
+                counter: Some(counter),
+                process_id: (!self.no_pid).then(|| u128::from(self.pid())),
+                random: out.as_str()[random..].to_string(),
+            });
+        }
+        if self.length_header {
+            let len = u128::try_from(self.prefix.len()).unwrap();
+            self.encode_into(out, len, 1)?;
+        }
+        if self.ocr_safe {
+            let check = luhn_check_char(&out.as_str()[self.head_len()..], OCR_ALPHABET);
+            out.push(check)?;
+        }
+
+        if self
+            .max_total_len
+            .is_some_and(|max| out.as_str().chars().count() > max)
+        {
+            return Err(PuidError::TooLong);
+        }
+        if self.max_bytes.is_some_and(|max| out.len() > max) {
+            return Err(PuidError::TooLong);
+        }
+
+        #[cfg(feature = "metrics")]
+        {
+            let step = self.counter_step.unwrap_or(1);
+            let wrapped = pins.counter.is_none() && counter.checked_add(u32::from(step)).is_none();
+            crate::metrics::record(counter, wrapped);
+        }
+        Ok(visible)
+    }
+
+    /// Returns the maximum length in bytes of the IDs, before any transform,
+    /// e.g. to size fixed-width columns or buffers ahead of generation.
+    ///
+    /// It is the exact length unless the entropy varies with
+    /// `entropy_range`, the alphabet mixes characters of different UTF-8
+    /// widths, or duplicates are retried with `RetryStrategy::BumpEntropy`.
+    #[must_use]
+    pub fn max_len(&self) -> usize {
+        self.len_with(self.padded_entropy(self.retried_entropy()))
+    }
+
+    /// Returns the maximum length in characters of the IDs, checked against
+    /// `max_total_len`, which is shorter than `max_len` with multi-byte
+    /// characters.
+    fn max_chars(&self) -> usize {
+        let entropy = self.padded_entropy(self.retried_entropy());
+        self.measure_with(entropy, |s| s.chars().count())
+    }
+
+    /// Returns the largest entropy including the characters added by
+    /// retrying duplicates with `RetryStrategy::BumpEntropy`.
+    fn retried_entropy(&self) -> usize {
+        let mut entropy = self.max_entropy();
+        if self.seen.is_some() && self.strategy == RetryStrategy::BumpEntropy {
+            entropy += usize::from(MAX_DEDUP_RETRIES);
+        }
+        entropy
+    }
+
+    /// Pads the random sequence of a decodable ID until the body is whole
+    /// base64 quanta.
+    fn padded_entropy(&self, mut entropy: usize) -> usize {
+        if self.decodable && !self.opaque {
+            while entropy < MAX_ENTROPY
+                && !(self.len_with(entropy) - self.head_len()).is_multiple_of(4)
+            {
+                entropy += 1;
+            }
+        }
+        entropy
+    }
+
+    /// Returns the largest entropy of the random sequence.
+    fn max_entropy(&self) -> usize {
+        self.entropy_max.unwrap_or(self.entropy)
+    }
+
+    /// Returns the length in bytes of the prefix and separator.
+    fn head_len(&self) -> usize {
+        let mut len = self.prefix.len();
+        if let Some(separator) = self.separator_char() {
+            len += separator.len_utf8();
+        }
+        len
+    }
+
+    /// Returns the length in bytes of the locale segment and its separator.
+    fn locale_len(&self) -> usize {
+        match (&self.locale, self.separator_char()) {
+            (Some(locale), Some(separator)) => locale.len() + separator.len_utf8(),
+            _ => 0,
+        }
+    }
+
+    /// Writes the separator before a field when delimiting the fields.
+    fn delimit<S: Sink>(&self, out: &mut S) -> PuidResult<()> {
+        if self.delimited {
+            out.push(self.separator.unwrap_or(DEFAULT_SEPARATOR))?;
+        }
+        Ok(())
+    }
+
+    /// Returns the length in bytes of the timestamp.
+    fn time_len(&self) -> usize {
+        if self.no_timestamp {
+            0
+        } else if self.iso_timestamp {
+            iso::ISO_TIME_LEN
+        } else {
+            let max = self.time_resolution().max();
+            self.encode(max, max).len()
+        }
+    }
+
+    /// Returns the resolution of the timestamp, milliseconds when in
+    /// ISO-8601 form.
+    fn time_resolution(&self) -> TimeResolution {
+        if self.iso_timestamp {
+            TimeResolution::Millis
+        } else {
+            self.resolution
+        }
+    }
+
+    /// Returns the maximum length in bytes of the IDs with the given entropy.
+    fn len_with(&self, entropy: usize) -> usize {
+        self.measure_with(entropy, str::len)
+    }
+
+    /// Returns the maximum length of the IDs with the given entropy, with
+    /// every part of the layout sized by `measure`.
+    fn measure_with(&self, entropy: usize, measure: fn(&str) -> usize) -> usize {
+        let width = |max: u128| measure(&self.encode(max, max));
+        let char_width = |c: &char| measure(c.encode_utf8(&mut [0; 4]));
+        // The version marker stays in base-36 whatever the digits
+        let marker_width = |max: u128| to_base36(max).len();
+        let mut len = measure(&self.prefix) + self.separator_char().map_or(0, |c| char_width(&c));
+        len += self.locale_len();
+        if self.opaque {
+            return len + OPAQUE_LEN;
+        }
+
+        let time_len = match self.no_timestamp || self.iso_timestamp {
+            true => self.time_len(),
+            false => width(self.time_resolution().max()),
+        };
+        len += marker_width(1) + time_len + width(u128::from(u32::MAX));
+        if !self.no_pid {
+            len += width(u128::from(u32::MAX));
+        }
+        match self.flags() {
+            0 => {}
+            flags if u128::from(flags) > FLAGS_MAX => len += marker_width(WIDE_FLAGS_MAX),
+            _ => len += marker_width(FLAGS_MAX),
+        }
+        if self.self_describing {
+            len += width(u128::from(u8::MAX));
+        }
+        if self.time_resolution() != TimeResolution::Millis {
+            len += width(RESOLUTION_MAX);
+        }
+        if self.thread_id {
+            len += width(u128::from(u32::MAX));
+        }
+        if self.tag.is_some() {
+            len += width(u128::from(u16::MAX));
+        }
+        if self.namespace.is_some() {
+            len += width(u128::from(u32::MAX));
+        }
+        if self.node_id.is_some() {
+            len += width(u128::from(u16::MAX));
+        }
+        if self.installation_id.is_some() {
+            len += width(u128::from(u64::MAX));
+        }
+        if self.ttl.is_some() {
+            len += width(TIME_MAX);
+        }
+        let char_len = self.alphabet.as_ref().map_or(1, |alphabet| {
+            alphabet.iter().map(char_width).max().unwrap_or(1)
+        });
+        len += entropy * char_len;
+        if let Some(group_len) = self.group_len {
+            len += entropy.div_ceil(group_len);
+        }
+        if self.length_header {
+            len += width(1);
+        }
+        if self.ocr_safe {
+            len += 1;
+        }
+        if self.delimited {
+            let fields = 4 - usize::from(self.no_pid) - usize::from(self.no_timestamp)
+                + usize::from(self.self_describing)
+                + usize::from(self.thread_id)
+                + usize::from(self.tag.is_some())
+                + usize::from(self.namespace.is_some())
+                + usize::from(self.node_id.is_some())
+                + usize::from(self.installation_id.is_some())
+                + usize::from(self.ttl.is_some())
+                + usize::from(self.time_resolution() != TimeResolution::Millis);
+            len += fields * char_width(&self.separator.unwrap_or(DEFAULT_SEPARATOR));
+        }
+        len
+    }
+
+    /// Returns the flags describing the optional segments of the layout.
+    fn flags(&self) -> u16 {
+        let mut flags = 0;
+        if self.length_header {
+            flags |= FLAG_LENGTH_HEADER;
+        }
+        if self.thread_id {
+            flags |= FLAG_THREAD_ID;
+        }
+        if self.tag.is_some() {
+            flags |= FLAG_TAG;
+        }
+        if self.scramble {
+            flags |= FLAG_SCRAMBLED;
+        }
+        if self.self_describing {
+            flags |= FLAG_ENTROPY;
+        }
+        if self.namespace.is_some() {
+            flags |= FLAG_NAMESPACE;
+        }
+        if self.iso_timestamp && !self.no_timestamp {
+            flags |= FLAG_ISO_TIME;
+        }
+        if self.delimited {
+            flags |= FLAG_DELIMITED;
+        }
+        if self.node_id.is_some() {
+            flags |= FLAG_NODE_ID;
+        }
+        if self.time_resolution() != TimeResolution::Millis {
+            flags |= FLAG_RESOLUTION;
+        }
+        if self.no_pid {
+            flags |= FLAG_NO_PID;
+        }
+        if self.ttl.is_some() {
+            flags |= FLAG_EXPIRY;
+        }
+        if self.crockford {
+            flags |= FLAG_CROCKFORD;
+        }
+        if self.no_timestamp {
+            flags |= FLAG_NO_TIME;
+        }
+        if self.installation_id.is_some() {
+            flags |= FLAG_INSTALLATION;
+        }
+        if self.readable {
+            flags |= FLAG_READABLE;
+        }
+        flags
+    }
+
+    /// Returns the bits of entropy of the random sequence.
+    #[allow(
+        clippy::cast_precision_loss,
+        clippy::cast_possible_truncation,
+        clippy::cast_sign_loss
+    )]
+    pub(crate) fn entropy_bits(&self) -> u32 {
+        let symbols = self.alphabet.as_ref().map_or(62, Vec::len) as f64;
+        (self.entropy as f64 * log2(symbols)) as u32
+    }
+
+    /// Returns the separator written between the prefix and the body, `None`
+    /// for numeric and unprefixed IDs.
+    pub(crate) fn separator_char(&self) -> Option<char> {
+        (!self.numeric && !self.no_prefix).then(|| self.separator.unwrap_or(DEFAULT_SEPARATOR))
+    }
+
+    /// Returns the digits of the numeric fields, `None` for base-36.
+    pub(crate) fn field_digits(&self) -> Option<&[char]> {
+        self.digits.as_deref()
+    }
+
+    /// Returns the layout version written after the separator, `None` for
+    /// opaque IDs which have no parseable layout.
+    pub(crate) fn layout_version(&self) -> Option<u8> {
+        match self.flags() {
+            _ if self.opaque => None,
+            0 => Some(VERSION),
+            flags if u128::from(flags) > FLAGS_MAX => Some(WIDE_VERSION),
+            _ => Some(EXTENDED_VERSION),
+        }
+    }
+
+    /// Returns whether the clock is frozen instead of following the system one.
+    pub(crate) fn has_frozen_clock(&self) -> bool {
+        self.time.is_some()
+    }
+
+    /// Checks that a lowercase configuration leaves no uppercase character.
+    fn check_lowercase(&self) -> PuidResult<()> {
+        if !self.lowercase {
+            return Ok(());
+        }
+        let lowercase = |chars: &Option<Vec<char>>| {
+            chars
+                .as_ref()
+                .is_some_and(|chars| !chars.iter().any(|c| c.is_uppercase()))
+        };
+        if self.prefix.chars().any(char::is_uppercase) {
+            return Err(PuidError::InvalidPrefix);
+        }
+        if self.separator_char().is_some_and(char::is_uppercase) {
+            return Err(PuidError::InvalidSeparator);
+        }
+        // The alphabet is reset to mixed case by `case` or `charset`
+        if !lowercase(&self.alphabet) || (self.digits.is_some() && !lowercase(&self.digits)) {
+            return Err(PuidError::InvalidAlphabet);
+        }
+        if self.iso_timestamp {
+            return Err(PuidError::ConflictingOptions);
+        }
+        Ok(())
+    }
+
+    /// Checks that a QR alphanumeric configuration stays within the set.
+    fn check_qr_alphanumeric(&self) -> PuidResult<()> {
+        if !self.qr_alphanumeric {
+            return Ok(());
+        }
+        let within = |chars: &Option<Vec<char>>| {
+            chars
+                .as_ref()
+                .is_some_and(|chars| chars.iter().all(|&c| QR_ALPHABET.contains(c)))
+        };
+        if !self.prefix.chars().all(|c| QR_ALPHABET.contains(c)) {
+            return Err(PuidError::InvalidPrefix);
+        }
+        if self
+            .separator_char()
+            .is_some_and(|separator| !QR_ALPHABET.contains(separator))
+        {
+            return Err(PuidError::InvalidSeparator);
+        }
+        if !within(&self.digits) || !within(&self.alphabet) {
+            return Err(PuidError::InvalidAlphabet);
+        }
+        if self.locale.is_some() || self.opaque {
+            return Err(PuidError::ConflictingOptions);
+        }
+        Ok(())
+    }
+
+    /// Checks that a sortable configuration keeps IDs in lexical time order.
+    fn check_sortable(&self) -> PuidResult<()> {
+        let ascending = self
+            .digits
+            .as_ref()
+            .is_none_or(|digits| digits.windows(2).all(|w| w[0] < w[1]));
+        if self.sortable
+            && (self.scramble
+                || self.opaque
+                || self.no_timestamp
+                || self.time_key.is_some()
+                || !ascending)
+        {
+            return Err(PuidError::Unsortable);
+        }
+        Ok(())
+    }
+
+    /// Checks that an encrypted timestamp is numeric.
+    fn check_encrypted(&self) -> PuidResult<()> {
+        if self.time_key.is_some() && self.iso_timestamp {
+            return Err(PuidError::ConflictingOptions);
+        }
+        Ok(())
+    }
+
+    /// Returns the number of values the fixed-width timestamp field holds.
+    fn time_domain(&self) -> u128 {
+        let base = self.digits.as_ref().map_or(36, Vec::len);
+        u128::try_from(base)
+            .unwrap()
+            .saturating_pow(u32::try_from(self.time_len()).unwrap())
+    }
+
+    /// Returns the minimum length of the prefix.
+    fn prefix_min_len(&self) -> usize {
+        self.min_prefix_len.unwrap_or(PREFIX_MIN_LEN)
+    }
+
+    /// Returns the maximum length of the prefix.
+    fn prefix_max_len(&self) -> usize {
+        self.max_prefix_len.unwrap_or(PREFIX_MAX_LEN)
+    }
+
+    /// Returns whether the prefix is missing, or shorter than the minimum or
+    /// longer than the maximum.
+    fn prefix_out_of_bounds(&self) -> bool {
+        if let Some(validator) = self.prefix_validator {
+            return self.prefix.is_empty() || !validator(&self.prefix);
+        }
+        self.prefix.is_empty()
+            || self.prefix.len() < self.prefix_min_len()
+            || self.prefix.len() > self.prefix_max_len()
+    }
+
+    /// Checks that the configured separator is a printable ASCII character
+    /// which cannot appear in the prefix or encoded fields.
+    fn check_separator(&self) -> PuidResult<()> {
+        let separator = self.separator.unwrap_or(DEFAULT_SEPARATOR);
+        if !separator.is_ascii_graphic() {
+            return Err(PuidError::InvalidSeparator);
+        }
+        if self.separator_collides() {
+            return Err(PuidError::SeparatorCollision);
+        }
+        Ok(())
+    }
+
+    /// Returns whether the configured separator collides with the prefix or
+    /// encoded fields.
+    pub(crate) fn separator_collides(&self) -> bool {
+        self.collides(self.separator.unwrap_or(DEFAULT_SEPARATOR))
+    }
+
+    /// Checks whether a character can appear in the prefix or encoded fields.
+    fn collides(&self, c: char) -> bool {
+        let in_digits = match &self.digits {
+            Some(digits) => digits.contains(&c),
+            None => c.is_ascii_digit() || c.is_ascii_lowercase(),
+        };
+        let in_alphabet = match &self.alphabet {
+            Some(alphabet) => alphabet.contains(&c),
+            None => c.is_ascii_alphanumeric(),
+        };
+        let in_groups = self.group_len.is_some() && c == '-';
+
+        c.is_ascii_alphanumeric()
+            || self.prefix.contains(c)
+            || in_digits
+            || in_alphabet
+            || in_groups
+    }
+
+    /// Encodes a numeric field with the configured digits or base-36,
+    /// zero-padded to the width of its `max` value.
+    fn encode(&self, v: u128, max: u128) -> String {
+        match &self.digits {
+            Some(digits) => pad(encode(v, digits), encode(max, digits).len(), digits[0]),
+            None => pad(to_base36(v), to_base36(max).len(), '0'),
+        }
+    }
+
+    /// Writes `v` into `out` like `encode`, without an intermediate string.
+    fn encode_into<S: Sink>(&self, out: &mut S, v: u128, max: u128) -> PuidResult<()> {
+        write_digits(out, v, max, self.digits.as_deref())
+    }
+
+    /// Writes a value of the version marker into `out`, always in base-36
+    /// so parsers read the flags before knowing the encoding.
+    fn marker_into<S: Sink>(&self, out: &mut S, v: u128, max: u128) -> PuidResult<()> {
+        write_digits(out, v, max, None)
+    }
+
+    /// Retrieves the time from the fixed clock, the injected one or the
+    /// system one.
+    fn time(&self) -> u128 {
+        match (self.time, &self.clock) {
+            (Some(time), _) => time,
+            (None, Some(clock)) => clock.now_millis(),
+            #[cfg(feature = "std")]
+            (None, None) => time(),
+            // Rejected by `validate` without `std`
+            #[cfg(not(feature = "std"))]
+            (None, None) => unreachable!(),
+        }
+    }
+
+    /// Returns the current timestamp in ticks of `resolution`, from the
+    /// monotonic sequence, the injected time or clock, or the system clock.
+    fn now_ticks(&self, resolution: TimeResolution) -> u128 {
+        if self.monotonic {
+            return u128::from(SEQUENCE.fetch_add(1, Ordering::SeqCst));
+        }
+        #[cfg(feature = "std")]
+        if self.time.is_none() && self.clock.is_none() {
+            return resolution.now();
+        }
+        resolution.ticks(self.time())
+    }
+
+    /// Retrieves the injected process ID or the current one.
+    #[cfg(feature = "std")]
+    fn pid(&self) -> u32 {
+        process_id(self.pid.unwrap_or_else(std::process::id))
+    }
+
+    /// Retrieves the injected process ID, 0 if none.
+    #[cfg(not(feature = "std"))]
+    fn pid(&self) -> u32 {
+        self.pid.unwrap_or(0)
+    }
+
+    /// Increments and fetches the per-second counter for an ID at `ms`, the
+    /// per-builder counter, the per-thread one when including the thread ID,
+    /// or the global one.
+    fn counter(&self, ms: u128) -> u32 {
+        let step = self.counter_step.unwrap_or(1);
+        if let Some(state) = &self.second_counter {
+            let mut state = state.lock().unwrap();
+            if state.0 != ms / 1000 {
+                *state = (ms / 1000, 0);
+            }
+            let value = state.1;
+            state.1 = value.wrapping_add(u32::from(step));
+            return value;
+        }
+        match &self.counter {
+            Some(counter) => next_by(counter, step),
+            #[cfg(feature = "std")]
+            None if self.thread_id => THREAD_COUNTER.with(|counter| next_relaxed(counter, step)),
+            None => next_by(&COUNTER, step),
+        }
+    }
+
+    /// Fills a buffer with random bytes from the seeded generator or `thread_rng()`.
+    fn fill_bytes(&self, buf: &mut [u8]) {
+        self.with_rng(|rng| rng.fill(buf));
+    }
+
+    /// Generates the random sequence into a stack buffer, one byte per
+    /// character, returning `None` if the alphabet is not ASCII.
+    fn rnd_ascii<'t>(&self, tail: &'t mut [u8]) -> Option<&'t str> {
+        if !self
+            .alphabet
+            .as_ref()
+            .is_none_or(|alphabet| alphabet.iter().all(char::is_ascii))
+        {
+            return None;
+        }
+        let base = self.alphabet.as_ref().map_or(BASE_62.len(), Vec::len);
+        let symbol = |i: usize| match &self.alphabet {
+            Some(alphabet) => alphabet[i] as u8,
+            None => BASE_62[i],
+        };
+
+        self.with_rng(|rng| {
+            if self.bulk_random && base <= 256 {
+                let mut k = 0;
+                rnd_bulk(rng, base, tail.len(), |i| {
+                    tail[k] = symbol(i);
+                    k += 1;
+                });
+            } else if self.alphabet.is_none() {
+                tail.iter_mut().for_each(|b| *b = rng.sample(Alphanumeric));
+            } else {
+                tail.iter_mut()
+                    .for_each(|b| *b = symbol(rng.gen_range(0..base)));
+            }
+        });
+        Some(core::str::from_utf8(tail).unwrap())
+    }
+
+    /// Runs `f` with the seeded generator or `thread_rng()`.
+    fn with_rng<T>(&self, f: impl FnOnce(&mut dyn RngCore) -> T) -> T {
+        match &self.rng {
+            Some(rng) => f(&mut *rng.lock().unwrap()),
+            #[cfg(feature = "std")]
+            None => f(&mut thread_rng()),
+            // Rejected by `validate` without `std`
+            #[cfg(not(feature = "std"))]
+            None => unreachable!(),
+        }
+    }
+
+    /// Generates the random sequence by encoding random bytes, drawn into
+    /// `bytes`, in the alphabet.
+    #[allow(
+        clippy::cast_precision_loss,
+        clippy::cast_possible_truncation,
+        clippy::cast_sign_loss
+    )]
+    fn rnd_encoded(&self, entropy: usize, bytes: &mut Vec<u8>) -> String {
+        let alphabet: Vec<char> = match &self.alphabet {
+            Some(alphabet) => alphabet.clone(),
+            None => BASE_62.iter().copied().map(char::from).collect(),
+        };
+        let bits = entropy as f64 * log2(alphabet.len() as f64);
+        bytes.resize((bits / 8.0) as usize, 0);
+        self.with_rng(|rng| rng.fill_bytes(bytes));
+        encode_bytes(bytes, &alphabet, entropy)
+    }
+
+    /// Generates the random sequence from the seeded generator or `thread_rng()`.
+    fn rnd_string(&self, entropy: usize) -> String {
+        if self.bulk_random && self.alphabet.as_ref().is_none_or(|a| a.len() <= 256) {
+            let (base, symbol): (usize, Box<dyn Fn(usize) -> char>) = match &self.alphabet {
+                Some(alphabet) => (alphabet.len(), Box::new(|i| alphabet[i])),
+                None => (BASE_62.len(), Box::new(|i| char::from(BASE_62[i]))),
+            };
+            return self.with_rng(|rng| rnd_string_bulk(rng, base, entropy, symbol));
+        }
+        self.with_rng(|rng| match &self.alphabet {
+            Some(alphabet) => rnd_string_from(rng, alphabet, entropy),
+            None => rnd_string(rng, entropy),
+        })
+    }
+}
+
+/// An output the ID is assembled into.
+trait Sink {
+    /// Appends a string slice.
+    fn push_str(&mut self, s: &str) -> PuidResult<()>;
+
+    /// Appends a character.
+    fn push(&mut self, c: char) -> PuidResult<()>;
+
+    /// Returns the length in bytes written so far.
+    fn len(&self) -> usize;
+
+    /// Returns what was written so far.
+    fn as_str(&self) -> &str;
+}
+
+impl Sink for String {
+    fn push_str(&mut self, s: &str) -> PuidResult<()> {
+        String::push_str(self, s);
+        Ok(())
+    }
+
+    fn push(&mut self, c: char) -> PuidResult<()> {
+        String::push(self, c);
+        Ok(())
+    }
+
+    fn len(&self) -> usize {
+        String::len(self)
+    }
+
+    fn as_str(&self) -> &str {
+        String::as_str(self)
+    }
+}
+
+#[cfg(feature = "arrayvec")]
+impl<const N: usize> Sink for arrayvec::ArrayString<N> {
+    fn push_str(&mut self, s: &str) -> PuidResult<()> {
+        self.try_push_str(s).map_err(|_| PuidError::TooLong)
+    }
+
+    fn push(&mut self, c: char) -> PuidResult<()> {
+        self.try_push(c).map_err(|_| PuidError::TooLong)
+    }
+
+    fn len(&self) -> usize {
+        arrayvec::ArrayString::len(self)
+    }
+
+    fn as_str(&self) -> &str {
+        arrayvec::ArrayString::as_str(self)
+    }
+}
+
+/// An infinite iterator of PUIDs borrowing a builder.
+///
+/// Created by iterating over `&PuidBuilder`, which validates the
+/// configuration once like `build()`; each item is a fresh ID, so callers
+/// bound it with `take(n)`. An invalid configuration yields its error as the
+/// only item.
+#[allow(clippy::module_name_repetitions)]
+#[derive(Debug)]
+pub struct PuidIter<'b, 'a> {
+    builder: &'b PuidBuilder<'a>,
+    valid: bool,
+    // The validation error, yielded as the first item
+    error: Option<PuidError>,
+}
+
+impl<'b, 'a> Iterator for PuidIter<'b, 'a> {
+    type Item = PuidResult<String>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.valid {
+            Some(self.builder.generate())
+        } else {
+            self.error.take().map(Err)
+        }
+    }
+}
+
+impl<'b, 'a> IntoIterator for &'b PuidBuilder<'a> {
+    type Item = PuidResult<String>;
+    type IntoIter = PuidIter<'b, 'a>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        let result = self.validate();
+        PuidIter {
+            builder: self,
+            valid: result.is_ok(),
+            error: result.err(),
+        }
+    }
+}
+
+/// An infinite iterator of PUIDs owning a validated builder.
+///
+/// Created by `PuidBuilder::iter`; each item is a fresh ID advancing the
+/// counter and reading the clock, so callers bound it with `take(n)`.
+#[allow(clippy::module_name_repetitions)]
+#[derive(Debug)]
+pub struct PuidIntoIter<'a> {
+    builder: PuidBuilder<'a>,
+}
+
+impl Iterator for PuidIntoIter<'_> {
+    type Item = String;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.builder.generate().ok()
+    }
+}
+
+/// Writes `v` padded to the width of `max` into `out`, in the base given by
+/// `digits` or base-36, without an intermediate string.
+fn write_digits<S: Sink>(
+    out: &mut S,
+    mut v: u128,
+    mut max: u128,
+    digits: Option<&[char]>,
+) -> PuidResult<()> {
+    let base = digits.map_or(u128::from(BASE_36), |digits| digits.len() as u128);
+    // Enough digits for a u128 in any base
+    let mut buf = ['0'; 128];
+    let mut at = buf.len();
+    while at == buf.len() || v > 0 || max > 0 {
+        let i = u32::try_from(v % base).unwrap();
+        at -= 1;
+        buf[at] = match digits {
+            Some(digits) => digits[i as usize],
+            None => char::from_digit(i, u32::from(BASE_36)).unwrap(),
+        };
+        v /= base;
+        max /= base;
+    }
+    buf[at..].iter().try_for_each(|&c| out.push(c))
+}
+
+/// Generates a Crockford base-32 encoded string from a `u128` value.
+pub(crate) fn to_base32_crockford(v: u128) -> String {
+    let digits: Vec<char> = CROCKFORD.chars().collect();
+    encode(v, &digits)
+}
+
+/// Decodes a string of the readable base-32 alphabet, case-insensitively.
+pub(crate) fn from_base32_readable(s: &str) -> Option<u128> {
+    if s.is_empty() {
+        return None;
+    }
+    s.chars().try_fold(0u128, |acc, c| {
+        let digit = READABLE_ALPHABET.find(c.to_ascii_lowercase())?;
+        acc.checked_mul(32)?
+            .checked_add(u128::try_from(digit).unwrap())
+    })
+}
+
+/// Decodes a Crockford base-32 string, case-insensitively and reading `I`
+/// and `L` as `1` and `O` as `0`.
+pub(crate) fn from_base32_crockford(s: &str) -> Option<u128> {
+    if s.is_empty() {
+        return None;
+    }
+    s.chars().try_fold(0u128, |acc, c| {
+        let c = match c.to_ascii_uppercase() {
+            'I' | 'L' => '1',
+            'O' => '0',
+            c => c,
+        };
+        let digit = CROCKFORD.find(c)?;
+        acc.checked_mul(32)?
+            .checked_add(u128::try_from(digit).unwrap())
+    })
+}
+
+/// Generates a base-36 encoded string from a `u128` value.
+pub(crate) fn to_base36(mut v: u128) -> String {
+    if v == 0 {
+        return "0".to_string();
+    }
+    // 16 characters cover most cases which is typical for base-36 encoding of a u128
+    let mut result = String::with_capacity(16);
+    while v > 0 {
+        result.push(
+            char::from_digit(
+                u32::try_from(v % u128::from(BASE_36)).unwrap(),
+                u32::from(BASE_36),
+            )
+            .unwrap(),
+        );
+        v /= u128::from(BASE_36);
+    }
+    result.chars().rev().collect()
+}
+
+/// Validates an alphabet has at least 2 characters without repetitions.
+fn alphabet(chars: &str) -> PuidResult<Vec<char>> {
+    let alphabet: Vec<char> = chars.chars().collect();
+    if alphabet.is_empty() {
+        return Err(PuidError::EmptyAlphabet);
+    }
+    let unique = alphabet
+        .iter()
+        .enumerate()
+        .all(|(i, c)| !alphabet[..i].contains(c));
+    if alphabet.len() < 2 || !unique {
+        return Err(PuidError::InvalidAlphabet);
+    }
+    Ok(alphabet)
+}
+
+/// Generates a string from a `u128` value in the base given by `digits`.
+fn encode(mut v: u128, digits: &[char]) -> String {
+    if v == 0 {
+        return digits[0].to_string();
+    }
+    let base = digits.len() as u128;
+    let mut result = Vec::with_capacity(16);
+    while v > 0 {
+        result.push(digits[usize::try_from(v % base).unwrap()]);
+        v /= base;
+    }
+    result.iter().rev().collect()
+}
+
+/// Generates a base-62 string of `width` characters from big-endian bytes.
+pub(crate) fn to_base62(bytes: &[u8], width: usize) -> String {
+    let mut number = bytes.to_vec();
+    let mut digits = Vec::with_capacity(width);
+    while number.iter().any(|&b| b != 0) {
+        // Long division of the big-endian number by 62
+        let mut remainder = 0u32;
+        for byte in &mut number {
+            let acc = (remainder << 8) | u32::from(*byte);
+            *byte = u8::try_from(acc / 62).unwrap();
+            remainder = acc % 62;
+        }
+        digits.push(BASE_62[remainder as usize]);
+    }
+    digits.resize(width.max(digits.len()), b'0');
+    digits.iter().rev().map(|&b| char::from(b)).collect()
+}
+
+/// Left-pads a string with `zero` up to `width` characters.
+pub(crate) fn pad(s: String, width: usize, zero: char) -> String {
+    let len = s.chars().count();
+    if len >= width {
+        return s;
+    }
+    core::iter::repeat_n(zero, width - len)
+        .chain(s.chars())
+        .collect()
+}
+
+/// Decodes a lowercase base-36 string into a `u128` value.
+pub(crate) fn from_base36(s: &str) -> Option<u128> {
+    if s.is_empty() {
+        return None;
+    }
+    s.chars().try_fold(0u128, |acc, c| {
+        let digit = match c {
+            '0'..='9' | 'a'..='z' => c.to_digit(u32::from(BASE_36))?,
+            _ => return None,
+        };
+        acc.checked_mul(u128::from(BASE_36))?
+            .checked_add(u128::from(digit))
+    })
+}
+
+/// Generates a random alphanumeric string of the specified length.
+pub(crate) fn rnd_string<R: Rng + ?Sized>(rng: &mut R, elements: usize) -> String {
+    rng.sample_iter(&Alphanumeric)
+        .take(elements)
+        .map(char::from)
+        .collect()
+}
+
+/// Generates a random string of the specified length from the given characters.
+fn rnd_string_from<R: Rng + ?Sized>(rng: &mut R, alphabet: &[char], elements: usize) -> String {
+    (0..elements)
+        .map(|_| alphabet[rng.gen_range(0..alphabet.len())])
+        .collect()
+}
+
+/// Encodes bytes as a big-endian number in the given digits, padded to
+/// `len` characters.
+#[allow(clippy::cast_possible_truncation)]
+fn encode_bytes(bytes: &[u8], digits: &[char], len: usize) -> String {
+    let base = digits.len() as u32;
+    let mut number = bytes.to_vec();
+    let mut out = vec![digits[0]; len];
+    for slot in out.iter_mut().rev() {
+        let mut rem = 0u32;
+        for byte in &mut number {
+            let acc = rem << 8 | u32::from(*byte);
+            *byte = u8::try_from(acc / base).unwrap();
+            rem = acc % base;
+        }
+        *slot = digits[rem as usize];
+    }
+    out.into_iter().collect()
+}
+
+/// Sums the Luhn mod N addends of the characters of `s` in `alphabet`, from
+/// the right starting with a factor of `factor`, skipping other characters.
+fn luhn_sum(s: &str, alphabet: &str, mut factor: usize) -> usize {
+    let n = alphabet.len();
+    s.chars()
+        .rev()
+        .filter_map(|c| alphabet.find(c))
+        .map(|point| {
+            let addend = factor * point;
+            factor = 3 - factor;
+            addend / n + addend % n
+        })
+        .sum()
+}
+
+/// Computes the Luhn mod N check character of `s` in an ASCII `alphabet`.
+fn luhn_check_char(s: &str, alphabet: &str) -> char {
+    let n = alphabet.len();
+    let point = (n - luhn_sum(s, alphabet, 2) % n) % n;
+    char::from(alphabet.as_bytes()[point])
+}
+
+/// Hashes a string with the 32-bit FNV-1a function, stable across releases.
+fn fnv1a(s: &str) -> u32 {
+    s.bytes().fold(0x811c_9dc5, |hash, b| {
+        (hash ^ u32::from(b)).wrapping_mul(0x0100_0193)
+    })
+}
+
+/// Hashes bytes prefixed by a block index with the 64-bit FNV-1a function,
+/// stable across releases.
+pub(crate) fn fnv1a_64(block: u64, bytes: &[u8]) -> u64 {
+    block
+        .to_le_bytes()
+        .iter()
+        .chain(bytes)
+        .fold(0xcbf2_9ce4_8422_2325, |hash, &b| {
+            (hash ^ u64::from(b)).wrapping_mul(0x0100_0000_01b3)
+        })
+}
+
+/// Replaces the body of an ID, starting at `at`, with a 128-bit hash of it
+/// encoded in base-36.
+fn opaque(id: &str, at: usize) -> String {
+    let hash = |block: u64| fnv1a_64(block, &id.as_bytes()[at..]);
+    let digest = u128::from(hash(0)) << 64 | u128::from(hash(1));
+    [&id[..at], &pad(to_base36(digest), OPAQUE_LEN, '0')].concat()
+}
+
+/// Generates a random string of the specified length from a buffer of random
+/// bytes, mapping each accepted byte onto one of `base` symbols.
+fn rnd_string_bulk<R: Rng + ?Sized>(
+    rng: &mut R,
+    base: usize,
+    elements: usize,
+    symbol: impl Fn(usize) -> char,
+) -> String {
+    let mut result = String::with_capacity(elements);
+    rnd_bulk(rng, base, elements, |i| result.push(symbol(i)));
+    result
+}
+
+/// Draws `n` indices below `base` from a buffer of random bytes, passing
+/// each one to `emit`.
+///
+/// Bytes at or above the largest multiple of `base` are rejected to keep the
+/// indices unbiased, refilling the buffer when it runs out.
+fn rnd_bulk<R: Rng + ?Sized>(rng: &mut R, base: usize, n: usize, mut emit: impl FnMut(usize)) {
+    let limit = 256 - 256 % base;
+    let mut buf = [0u8; 256];
+    let mut remaining = n;
+    while remaining > 0 {
+        // Twice the remaining length covers the rejections of most alphabets
+        let chunk = &mut buf[..(remaining * 2).min(256)];
+        rng.fill(&mut *chunk);
+        for &b in chunk
+            .iter()
+            .filter(|&&b| usize::from(b) < limit)
+            .take(remaining)
+        {
+            emit(usize::from(b) % base);
+            remaining -= 1;
+        }
+    }
+}
+
+/// Returns how many more values a counter yields before it wraps.
+fn headroom(counter: &AtomicU32) -> u32 {
+    u32::MAX - counter.load(Ordering::SeqCst)
+}
+
+/// Fetches an atomic counter and advances it by `step`, wrapping around.
+fn next_by(counter: &AtomicU32, step: u8) -> u32 {
+    counter.fetch_add(u32::from(step), Ordering::SeqCst)
+}
+
+/// Moves a system clock tick past the one of the last counter wrap, so IDs
+/// numbered after the counter wraps within a tick never repeat the
+/// timestamp and counter of one numbered before, recording the wrap when
+/// `wrapped`.
+#[allow(clippy::cast_possible_truncation)]
+fn wrap_floor(resolution: TimeResolution, tick: u128, wrapped: bool) -> u128 {
+    let tick = match WRAP_FLOOR.load(Ordering::SeqCst) {
+        0 => tick,
+        // The first tick starting at or after the floor
+        floor => {
+            let floor = u128::from(floor) + resolution.millis(1).saturating_sub(1);
+            tick.max(resolution.ticks(floor))
+        }
+    };
+    if wrapped {
+        let ms = resolution.millis(tick) as u64;
+        WRAP_FLOOR.fetch_max(ms + 1, Ordering::SeqCst);
+    }
+    tick
+}
+
+/// Replaces an unavailable process ID of 0 with a random node ID, stable for
+/// the lifetime of the process.
+#[cfg(feature = "std")]
+fn process_id(pid: u32) -> u32 {
+    if pid != 0 {
+        return pid;
+    }
+    *NODE_ID.get_or_init(|| {
+        PID_FALLBACK.store(true, Ordering::Relaxed);
+        log::warn!("process ID unavailable, falling back to a random node ID");
+        thread_rng().gen_range(1..=u32::MAX)
+    })
+}
+
+/// Computes the binary logarithm of a positive number.
+#[cfg(feature = "std")]
+fn log2(x: f64) -> f64 {
+    x.log2()
+}
+
+/// Computes the binary logarithm of a positive number without `std`, from
+/// its exponent and a series for the logarithm of its mantissa.
+#[cfg(not(feature = "std"))]
+#[allow(clippy::cast_possible_wrap)]
+fn log2(x: f64) -> f64 {
+    if !x.is_finite() {
+        return x;
+    }
+    let bits = x.to_bits();
+    let exponent = ((bits >> 52) & 0x7ff) as i32 - 1023;
+    let mantissa = f64::from_bits(bits & 0x000f_ffff_ffff_ffff | 0x3ff0_0000_0000_0000);
+    // ln(m) = 2 atanh((m - 1) / (m + 1)), with the ratio below 1/3
+    let t = (mantissa - 1.0) / (mantissa + 1.0);
+    let (mut term, mut sum) = (t, 0.0);
+    for k in 0..24 {
+        sum += term / f64::from(2 * k + 1);
+        term *= t * t;
+    }
+    f64::from(exponent) + 2.0 * sum / core::f64::consts::LN_2
+}
+
+/// Rounds a number up to the nearest integer.
+#[cfg(feature = "std")]
+fn ceil(x: f64) -> f64 {
+    x.ceil()
+}
+
+/// Rounds a non-negative number up to the nearest integer without `std`.
+#[cfg(not(feature = "std"))]
+#[allow(
+    clippy::cast_precision_loss,
+    clippy::cast_possible_truncation,
+    clippy::cast_sign_loss
+)]
+fn ceil(x: f64) -> f64 {
+    let truncated = x as u64 as f64;
+    if truncated < x {
+        truncated + 1.0
+    } else {
+        truncated
+    }
+}
+
+/// Returns the identifier of the current thread, 0 without `std`.
+fn thread_id() -> u32 {
+    #[cfg(feature = "std")]
+    return THREAD_ID.with(|id| *id);
+    #[cfg(not(feature = "std"))]
+    0
+}
+
+/// Fetches a counter and advances it by `step` without synchronization,
+/// wrapping around.
+#[cfg(feature = "std")]
+fn next_relaxed(counter: &AtomicU32, step: u8) -> u32 {
+    let i = counter.load(Ordering::Relaxed);
+    counter.store(i.wrapping_add(u32::from(step)), Ordering::Relaxed);
+    i
+}
+
+/// Retrieves the current system time in milliseconds since the UNIX epoch.
+#[cfg(feature = "std")]
+pub(crate) fn time() -> u128 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap()
+        .as_millis()
+}
+
+/// Lowercases a locale tag and removes its hyphen, returning `None` unless it
+/// is a 2 or 3 letter language with an optional 2 letter region.
+fn compact_locale(tag: &str) -> Option<String> {
+    let (language, region) = match tag.split_once(['-', '_']) {
+        Some((_, "")) => return None,
+        Some((language, region)) => (language, region),
+        None => (tag, ""),
+    };
+    let letters = |s: &str| s.chars().all(|c| c.is_ascii_alphabetic());
+    if !(2..=3).contains(&language.len())
+        || !matches!(region.len(), 0 | 2)
+        || !letters(language)
+        || !letters(region)
+    {
+        return None;
+    }
+    Some([language, region].concat().to_ascii_lowercase())
+}
+
+/// Validates the prefix for length and alphanumeric characters.
+fn validate(prefix: &str) -> bool {
+    validate_with(prefix, PREFIX_MAX_LEN)
+}
+
+/// Validates the prefix for alphanumeric characters and a length up to `max`.
+pub(crate) fn validate_with(prefix: &str, max: usize) -> bool {
+    (PREFIX_MIN_LEN..=max).contains(&prefix.len())
+        && prefix.chars().all(|c| c.is_ascii_alphanumeric())
+}
+
+#[doc(hidden)]
+#[cfg(feature = "std")]
+#[deprecated(since = "0.1.0", note = "Deprecated in favour of Puid::builder()")]
+#[allow(clippy::must_use_candidate)]
+// Composes the different parts of the ID.
+pub fn puid(pref: &str, elements: u8) -> String {
+    assert!(
+        validate(pref),
+        "Prefix cannot be longer than 4 characters and with non-alphanumeric characters."
+    );
+
+    [
+        pref,
+        "_",
+        &to_base36(time()),
+        // The counter keeps a fixed width to delimit it from the timestamp
+        &pad(
+            to_base36(u128::from(next_by(&COUNTER, 1))),
+            to_base36(u128::from(u32::MAX)).len(),
+            '0',
+        ),
+        &to_base36(u128::from(process_id(std::process::id()))),
+        &rnd_string(&mut thread_rng(), usize::from(elements)),
+    ]
+    .concat()
+}
+
+/// Composes an ID from a prefix and a number of random characters, like the
+/// deprecated `puid()`, but with the fixed-width fields of
+/// `Puid::builder()`, so the output can be parsed back by `Puid::parse`.
+///
+/// Returns `PuidError::InvalidPrefix` if the prefix is invalid instead of
+/// panicking.
+pub fn puid_v2(pref: &str, elements: u8) -> PuidResult<String> {
+    PuidBuilder::new()
+        .prefix(pref)?
+        .entropy(usize::from(elements))
+        .build()
+}
+
+/// Abstract the ID generation for easy usage.
+///
+/// With default size of 12 random characters at the end.
+///
+/// ```rust
+/// puid::puid!("foo");
+/// ```
+///
+/// With custom size of 24 random characters at the end.
+///
+/// ```rust
+/// puid::puid!("bar", 24);
+/// ```
+#[cfg(feature = "std")]
+#[macro_export]
+#[deprecated(since = "0.1.0", note = "Deprecated in favour of Puid::builder()")]
+macro_rules! puid {
+    // Default puid with size of 12 random characters at the end.
+    ($pref:expr) => {
+        $crate::puid($pref, 12)
+    };
+
+    // puid with custom size of random characters at the end.
+    ($pref:expr, $elements:expr) => {
+        $crate::puid($pref, $elements)
+    };
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[cfg(feature = "std")]
+    use std::{collections::HashMap, thread};
+
+    // Pins the clock, counter and random generator of a builder.
+    fn seeded(builder: PuidBuilder<'_>, seed: u64) -> PuidBuilder<'_> {
+        PuidBuilder {
+            time: Some(1651312057000),
+            counter: Some(AtomicU32::new(0)),
+            ..builder.seed(seed)
+        }
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn to_base36_test() {
+        assert_eq!(to_base36(1651312057), "rb5cjd");
+        assert_eq!(to_base36(0), "0");
+        assert_eq!(to_base32_crockford(0), "0");
+
+        // Zero fields keep their full width
+        let builder = Puid::builder().prefix("foo").unwrap();
+        let id = builder.build_with_time(0).unwrap();
+        assert_eq!(id.len(), 28 + usize::from(DEFAULT_ENTROPY));
+        assert!(id.starts_with("foo_1000000000"));
+        assert_eq!(Puid::timestamp_of(&id).unwrap(), 0);
+    }
+
+    #[test]
+    fn from_base36_test() {
+        assert_eq!(from_base36("rb5cjd"), Some(1651312057));
+        assert_eq!(from_base36("RB5CJD"), None);
+        assert_eq!(from_base36(""), None);
+    }
+
+    #[test]
+    fn encode_test() {
+        let digits: Vec<char> = "0123456789abcdefghijklmnopqrstuvwxyz".chars().collect();
+        assert_eq!(encode(1651312057, &digits), to_base36(1651312057));
+        assert_eq!(encode(5, &['0', '1']), "101");
+    }
+
+    #[test]
+    fn to_base62_test() {
+        assert_eq!(to_base62(&[0], 3), "000");
+        assert_eq!(to_base62(&[0, 62], 3), "010");
+        assert_eq!(
+            to_base62(&[0xff; 20], KSUID_LEN),
+            "aWgEPTl1tmebfsQzFP4bxwgy80V"
+        );
+    }
+
+    #[test]
+    fn pad_test() {
+        assert_eq!(pad("rb5cjd".to_string(), 9, '0'), "000rb5cjd");
+        assert_eq!(pad("rb5cjd".to_string(), 4, '0'), "rb5cjd");
+    }
+
+    #[test]
+    fn alphabet_test() {
+        assert_eq!(alphabet("ab").unwrap(), vec!['a', 'b']);
+        assert!(matches!(alphabet("a"), Err(PuidError::InvalidAlphabet)));
+        assert!(matches!(alphabet("aba"), Err(PuidError::InvalidAlphabet)));
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn rnd_string_test() {
+        assert_eq!(rnd_string(&mut thread_rng(), 12).len(), 12);
+    }
+
+    #[test]
+    fn encode_bytes_test() {
+        let digits: Vec<char> = "0123456789".chars().collect();
+        assert_eq!(encode_bytes(&[1, 0], &digits, 5), "00256");
+        assert_eq!(encode_bytes(&[], &digits, 2), "00");
+
+        let digits: Vec<char> = BASE_64_URL.chars().collect();
+        assert_eq!(encode_bytes(b"pui", &digits, 4), "cHVp");
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn rnd_string_from_test() {
+        let s = rnd_string_from(&mut thread_rng(), &['a', 'b'], 12);
+        assert_eq!(s.len(), 12);
+        assert!(s.chars().all(|c| c == 'a' || c == 'b'));
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn rnd_string_bulk_test() {
+        let s = rnd_string_bulk(&mut thread_rng(), 3, 200, |i| ['a', 'b', 'c'][i]);
+        assert_eq!(s.len(), 200);
+        assert!(s.chars().all(|c| "abc".contains(c)));
+    }
+
+    #[test]
+    fn fnv1a_test() {
+        assert_eq!(fnv1a(""), 0x811c_9dc5);
+        assert_eq!(fnv1a("a"), 0xe40c_292c);
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn puid_legacy_test() {
+        let id = puid("foo", 12);
+        let pid = to_base36(u128::from(process_id(std::process::id())));
+        assert_eq!(id.len(), 4 + 8 + 7 + pid.len() + 12);
+        assert!(from_base36(&id[12..19]).is_some_and(|c| c <= u128::from(u32::MAX)));
+        assert_eq!(&id[19..19 + pid.len()], pid);
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn puid_v2_test() {
+        let id = puid_v2("foo", 12).unwrap();
+        let parts = Puid::parse(&id).unwrap();
+        assert_eq!(parts.prefix, "foo");
+        assert_eq!(parts.random.len(), 12);
+        assert!(parts.counter.is_some());
+
+        assert!(matches!(puid_v2("", 12), Err(PuidError::InvalidPrefix)));
+    }
+
+    #[test]
+    fn puid_content_addressed_test() {
+        let a = Puid::content_addressed("req", b"order 42", 24).unwrap();
+        assert_eq!(a, Puid::content_addressed("req", b"order 42", 24).unwrap());
+        assert_ne!(a, Puid::content_addressed("req", b"order 43", 24).unwrap());
+        assert!(a.starts_with("req_"));
+        assert_eq!(a.len(), 4 + 24);
+        assert!(a[4..].chars().all(|c| c.is_ascii_alphanumeric()));
+
+        assert!(Puid::content_addressed("", b"order 42", 24).is_err());
+        assert!(matches!(
+            Puid::content_addressed("req", b"order 42", MAX_ENTROPY + 1),
+            Err(PuidError::EntropyOutOfRange)
+        ));
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn counter_test() {
+        // A local counter, as other tests advance the global one concurrently
+        let counter = AtomicU32::new(0);
+        let a = next_by(&counter, 1); // 0
+        let b = next_by(&counter, 1);
+        assert!(a + 1 == b);
+        assert_eq!(next_by(&counter, 1), 2);
+
+        counter.store(u32::MAX - 100, Ordering::SeqCst);
+        thread::scope(|s| {
+            s.spawn(|| {
+                for _ in 0..100 {
+                    let _ = next_by(&counter, 1);
+                }
+            });
+        });
+        assert_eq!(next_by(&counter, 1), u32::MAX);
+        assert_eq!(next_by(&counter, 1), 0);
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn puid_builder_prefix_validator_test() {
+        let dotted = |prefix: &str| {
+            prefix.len() <= 16
+                && prefix
+                    .chars()
+                    .all(|c| c.is_ascii_alphanumeric() || c == '.' || c == '-')
+        };
+        let builder = || Puid::builder().prefix_validator(dotted);
+        let id = builder().prefix("org.billing").unwrap().build().unwrap();
+        assert!(id.starts_with("org.billing_1"));
+        assert_eq!(id.len(), 11 + 25 + usize::from(DEFAULT_ENTROPY));
+
+        assert!(builder().prefix("org.billing.invoices").is_err());
+        assert!(builder().prefix("org billing").is_err());
+        assert!(builder().prefix("").is_err());
+        // The default check applies without a validator
+        assert!(Puid::builder().prefix("org.billing").is_err());
+
+        assert!(matches!(
+            builder()
+                .prefix("org.billing")
+                .unwrap()
+                .separator('.')
+                .build(),
+            Err(PuidError::SeparatorCollision)
+        ));
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn puid_builder_lowercase_test() {
+        let builder = || Puid::builder().prefix("foo").unwrap().lowercase(true);
+        for _ in 0..100 {
+            let id = builder().entropy(64).build().unwrap();
+            assert_eq!(id, id.to_lowercase());
+        }
+        let id = builder().alphabet("xyz").unwrap().build().unwrap();
+        assert!(id[28..].chars().all(|c| "xyz".contains(c)));
+
+        assert!(matches!(
+            builder().alphabet("xyZ").unwrap().build(),
+            Err(PuidError::InvalidAlphabet)
+        ));
+        assert!(matches!(
+            builder().case(Case::Mixed).build(),
+            Err(PuidError::InvalidAlphabet)
+        ));
+        assert!(matches!(
+            builder().crockford_base32(true).build(),
+            Err(PuidError::InvalidAlphabet)
+        ));
+        assert!(matches!(
+            builder().iso_timestamp(true).build(),
+            Err(PuidError::ConflictingOptions)
+        ));
+        assert!(matches!(
+            Puid::builder()
+                .prefix("Foo")
+                .unwrap()
+                .lowercase(true)
+                .build(),
+            Err(PuidError::InvalidPrefix)
+        ));
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn puid_builder_max_len_test() {
+        let builder = || Puid::builder().prefix("foo").unwrap();
+        let configs = [
+            builder(),
+            builder().include_pid(false).node_id(7),
+            builder().crockford_base32(true).sortable(true),
+            builder().decodable(true),
+            builder().opaque(true),
+            builder().ocr_safe(),
+            builder().locale("en-us").unwrap().entropy(512),
+        ];
+        for builder in configs {
+            let len = builder.max_len();
+            assert_eq!(builder.build().unwrap().len(), len);
+        }
+
+        // Retries may add random characters
+        let bumping = builder()
+            .dedup(true)
+            .dedup_strategy(RetryStrategy::BumpEntropy);
+        assert_eq!(
+            bumping.max_len(),
+            28 + usize::from(DEFAULT_ENTROPY) + usize::from(MAX_DEDUP_RETRIES)
+        );
+
+        let builder = builder().entropy_range(8, 16).unwrap();
+        assert_eq!(builder.max_len(), 28 + 16);
+        assert!((&builder)
+            .into_iter()
+            .take(100)
+            .all(|id| id.unwrap().len() <= 28 + 16));
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn puid_builder_dual_test() {
+        let pairs: Vec<_> = (0..100)
+            .map(|_| Puid::builder().prefix("usr").unwrap().dual().unwrap())
+            .collect();
+        assert!(pairs.windows(2).all(|w| w[0].1 < w[1].1));
+        assert_eq!(
+            pairs.iter().map(|p| &p.0).collect::<HashSet<_>>().len(),
+            100
+        );
+        assert!(pairs.iter().all(|(id, _)| id.starts_with("usr_")));
+
+        // The public ID is the opaque ID numbered by the internal one
+        let builder = || seeded(Puid::builder().prefix("usr").unwrap(), 7).opaque(true);
+        let (public, internal) = builder().dual().unwrap();
+        let counter = u32::try_from(internal).unwrap();
+        assert_eq!(public, builder().build_with_counter(counter).unwrap());
+        assert_ne!(public, builder().build_with_counter(counter + 1).unwrap());
+
+        assert!(Puid::builder().dual().is_err());
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn concurrent_uniqueness_test() {
+        // No random characters, so only the timestamp and counter tell IDs apart
+        let builder = Puid::builder().prefix("foo").unwrap().entropy(0);
+        let ids: Vec<String> = thread::scope(|s| {
+            let handles: Vec<_> = (0..8)
+                .map(|_| {
+                    s.spawn(|| {
+                        (0..12_500)
+                            .map(|_| builder.generate().unwrap())
+                            .collect::<Vec<_>>()
+                    })
+                })
+                .collect();
+            handles
+                .into_iter()
+                .flat_map(|handle| handle.join().unwrap())
+                .collect()
+        });
+        assert_eq!(ids.len(), 100_000);
+        assert_eq!(ids.iter().collect::<HashSet<_>>().len(), 100_000);
+
+        let builder = Puid::builder()
+            .prefix("foo")
+            .unwrap()
+            .entropy(0)
+            .counter_start(u32::MAX - 1);
+        let ids: Vec<_> = (&builder).into_iter().take(3).map(Result::unwrap).collect();
+        let times: Vec<_> = ids
+            .iter()
+            .map(|id| Puid::parse(id).unwrap().timestamp_ms.unwrap())
+            .collect();
+        assert!(times[0] <= times[1]);
+        // The counter wrapped, so the timestamp had to advance
+        assert!(times[2] > times[1]);
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn headroom_test() {
+        let builder = Puid::builder().prefix("foo").unwrap().counter_start(0);
+        let counter = builder.counter.as_ref().unwrap();
+        assert_eq!(headroom(counter), u32::MAX);
+
+        for id in (&builder).into_iter().take(10) {
+            id.unwrap();
+        }
+        assert_eq!(headroom(counter), u32::MAX - 10);
+        let _ = Puid::counter_headroom();
+    }
+
+    #[test]
+    fn min_entropy_for_test() {
+        assert_eq!(Puid::min_entropy_for(0, 0.5, 62), 0);
+        assert_eq!(Puid::min_entropy_for(1, 0.5, 62), 0);
+        assert_eq!(Puid::min_entropy_for(2, 0.5, 2), 1);
+        assert_eq!(Puid::min_entropy_for(36, 0.5, 36), 2);
+        assert_eq!(Puid::min_entropy_for(1_000, 0.001, 62), 5);
+        assert_eq!(Puid::min_entropy_for(1_000_000, 1e-9, 62), 12);
+        assert_eq!(Puid::min_entropy_for(1_000, 0.001, 1), u8::MAX);
+
+        let mut last = 0;
+        for m in [10, 1_000, 100_000, 10_000_000, 1_000_000_000] {
+            let k = Puid::min_entropy_for(m, 1e-6, 62);
+            assert!(k >= last);
+            last = k;
+        }
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn default_entropy_test() {
+        let expected = if cfg!(feature = "default-entropy-16") {
+            16
+        } else if cfg!(feature = "default-entropy-24") {
+            24
+        } else {
+            12
+        };
+        assert_eq!(Puid::default_entropy(), expected);
+        let id = Puid::builder().prefix("foo").unwrap().build().unwrap();
+        assert_eq!(id.len(), 28 + usize::from(expected));
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn build_mixed_test() {
+        let ids = Puid::build_mixed(&[("user", 12, 100), ("order", 16, 50)]).unwrap();
+        assert_eq!(ids.len(), 150);
+        assert_eq!(ids.iter().filter(|id| id.starts_with("user_")).count(), 100);
+        assert_eq!(ids.iter().filter(|id| id.starts_with("order_")).count(), 50);
+        assert!(ids[100..].iter().all(|id| id.len() == 6 + 24 + 16));
+        assert_eq!(ids.iter().collect::<HashSet<_>>().len(), 150);
+
+        assert!(matches!(
+            Puid::build_mixed(&[("user", 12, 1), ("bad_one", 12, 1)]),
+            Err(PuidError::InvalidPrefix)
+        ));
+
+        let specs = [("user", 12, 1), ("user", 16, 1)];
+        assert!(matches!(
+            Puid::build_mixed(&specs),
+            Err(PuidError::DuplicatePrefix)
+        ));
+        assert_eq!(Puid::build_mixed_with(&specs, true).unwrap().len(), 2);
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn build_avoiding_test() {
+        // The same seed under a frozen clock collides on the first attempt
+        let builder = || seeded(Puid::builder().prefix("foo").unwrap(), 42);
+        let first = builder().generate().unwrap();
+        assert_eq!(builder().generate().unwrap(), first);
+        let existing = HashSet::from([first.clone()]);
+        let id = builder().generate_avoiding(&existing).unwrap();
+        assert_ne!(id, first);
+        assert!(!existing.contains(&id));
+
+        let id = Puid::build_avoiding("foo", 12, &existing).unwrap();
+        assert!(id.starts_with("foo_1"));
+        assert!(matches!(
+            Puid::build_avoiding("bad_one", 12, &existing),
+            Err(PuidError::InvalidPrefix)
+        ));
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn process_id_fallback_test() {
+        assert_eq!(process_id(42), 42);
+
+        let builder = PuidBuilder {
+            pid: Some(0),
+            ..Puid::builder().prefix("foo").unwrap()
+        };
+        let a = builder.pid();
+        assert_ne!(a, 0);
+        assert_eq!(builder.pid(), a);
+        assert_eq!(process_id(0), a);
+        assert!(Puid::using_pid_fallback());
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn validate_test() {
+        let tests = HashMap::from([
+            ("Valid prefix for 1 character long", ("f", true)),
+            ("Valid prefix for 2 character long", ("fo", true)),
+            ("Valid prefix for 3 character long", ("foo", true)),
+            ("Valid prefix for 4 character long", ("quux", true)),
+            ("Valid prefix for alphanumeric characters", ("b4r", true)),
+            (
+                "Invalid prefix for non-alphanumeric characters",
+                ("bäz", false),
+            ),
+            ("Invalid prefix with empty value", ("", false)),
+        ]);
+        for (desc, t) in tests {
+            assert_eq!(validate(t.0), t.1, "{desc}");
+        }
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn puid_builder_test() {
+        let id = Puid::builder().prefix("foo").unwrap().build();
+        assert!(id.is_ok());
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn puid_builder_build_redacted_test() {
+        let (id, redacted) = Puid::builder()
+            .prefix("foo")
+            .unwrap()
+            .build_redacted()
+            .unwrap();
+
+        let visible = redacted.strip_suffix("****").unwrap();
+        assert!(visible.starts_with("foo_"));
+        assert!(visible.len() > "foo_".len());
+        assert!(id.starts_with(visible));
+        assert!(id.len() > redacted.len());
+        assert!(id.chars().skip(4).all(|c| c.is_ascii_alphanumeric()));
+
+        // The full form is a regular ID, visible up to the timestamp
+        assert!(Puid::validate_id(&id).is_ok());
+        let parts = Puid::parse(&id).unwrap();
+        assert_eq!(parts.prefix, "foo");
+        let time = to_base36(parts.timestamp_ms.unwrap());
+        assert!(visible.starts_with("foo_1") && visible.ends_with(&time));
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn puid_builder_readable_test() {
+        let id = Puid::builder()
+            .prefix("foo")
+            .unwrap()
+            .readable()
+            .build()
+            .unwrap();
+
+        let body = id.strip_prefix("foo_").unwrap();
+        assert!(!body.contains(['0', 'O', 'o', '1', 'l', 'I']));
+        assert!(body
+            .chars()
+            .all(|c| c == '-' || READABLE_ALPHABET.contains(c)));
+
+        let groups: Vec<&str> = body.split('-').collect();
+        assert_eq!(
+            groups.len(),
+            1 + usize::from(DEFAULT_ENTROPY) / READABLE_GROUP_LEN
+        );
+        assert!(groups[1..].iter().all(|g| g.len() == READABLE_GROUP_LEN));
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn puid_builder_readable_parse_test() {
+        let id = Puid::builder()
+            .prefix("foo")
+            .unwrap()
+            .readable()
+            .clock(|| 1_651_312_057_000)
+            .build()
+            .unwrap();
+        assert!(Puid::validate_id(&id).is_ok());
+        assert_eq!(
+            Puid::detect_format(&id).unwrap().encoding,
+            Encoding::Readable
+        );
+
+        let parts = Puid::parse(&id).unwrap();
+        assert_eq!(parts.prefix, "foo");
+        assert_eq!(parts.timestamp_ms, Some(1_651_312_057_000));
+        assert_eq!(
+            parts.random.replace('-', "").len(),
+            usize::from(DEFAULT_ENTROPY)
+        );
+
+        // An installation ID leaves no room in the flags for the alphabet
+        assert!(matches!(
+            Puid::builder()
+                .prefix("foo")
+                .unwrap()
+                .readable()
+                .installation_id(7)
+                .build(),
+            Err(PuidError::ConflictingOptions)
+        ));
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn puid_builder_into_iter_test() {
+        let builder = Puid::builder().prefix("foo").unwrap();
+
+        let mut ids = Vec::new();
+        for id in (&builder).into_iter().take(5) {
+            ids.push(id.unwrap());
+        }
+        assert_eq!(ids.len(), 5);
+        assert!(ids.iter().all(|id| id.starts_with("foo_")));
+        assert!(builder.build().is_ok());
+
+        // An invalid configuration yields its error once
+        let builder = Puid::builder()
+            .prefix("foo")
+            .unwrap()
+            .entropy(MAX_ENTROPY + 1);
+        let mut ids = (&builder).into_iter();
+        assert!(matches!(
+            ids.next(),
+            Some(Err(PuidError::EntropyOutOfRange))
+        ));
+        assert!(ids.next().is_none());
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn puid_builder_alphabets_test() {
+        let id = Puid::builder()
+            .prefix("foo")
+            .unwrap()
+            .numeric_alphabet("0123456789")
+            .unwrap()
+            .tail_alphabet("xy")
+            .unwrap()
+            .build()
+            .unwrap();
+
+        // 1 for the version, 15 for the time, 10 for the counter and 10 for the process ID
+        let body = id.strip_prefix("foo_").unwrap();
+        let (fields, tail) = body.split_at(1 + 15 + 10 + 10);
+        assert!(fields.starts_with('1'));
+        assert!(fields.chars().all(|c| c.is_ascii_digit()));
+        assert_eq!(tail.len(), usize::from(DEFAULT_ENTROPY));
+        assert!(tail.chars().all(|c| c == 'x' || c == 'y'));
+
+        let builder = Puid::builder().prefix("foo").unwrap();
+        assert!(builder.numeric_alphabet("00").is_err());
+        let builder = Puid::builder().prefix("foo").unwrap();
+        assert!(builder.tail_alphabet("").is_err());
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn puid_builder_separator_test() {
+        let id = Puid::builder()
+            .prefix("foo")
+            .unwrap()
+            .separator('-')
+            .build()
+            .unwrap();
+        assert!(id.starts_with("foo-"));
+
+        let id = Puid::builder()
+            .prefix("foo")
+            .unwrap()
+            .separator('a')
+            .build();
+        assert!(matches!(id, Err(PuidError::SeparatorCollision)));
+
+        let id = Puid::builder()
+            .prefix("foo")
+            .unwrap()
+            .readable()
+            .separator('-')
+            .build();
+        assert!(matches!(id, Err(PuidError::SeparatorCollision)));
+
+        for separator in [' ', '\n', 'é'] {
+            let id = Puid::builder()
+                .prefix("foo")
+                .unwrap()
+                .separator(separator)
+                .build();
+            assert!(matches!(id, Err(PuidError::InvalidSeparator)));
+        }
+        let id = Puid::builder().prefix("foo").unwrap().build().unwrap();
+        assert_eq!(&id[3..4], "_");
+    }
+
+    #[cfg(feature = "arrayvec")]
+    #[test]
+    fn puid_builder_build_inline_test() {
+        let builder = seeded(Puid::builder().prefix("foo").unwrap(), 42);
+        let inline = builder.build_inline::<64>().unwrap();
+        let id = seeded(Puid::builder().prefix("foo").unwrap(), 42)
+            .build()
+            .unwrap();
+        assert_eq!(inline.as_str(), id);
+
+        let inline = builder.build_inline::<8>();
+        assert!(matches!(inline, Err(PuidError::TooLong)));
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn puid_builder_monotonic_only_test() {
+        let builder = PuidBuilder {
+            time: Some(1651312057000),
+            ..Puid::builder().prefix("foo").unwrap().monotonic_only()
+        };
+        let ids: Vec<String> = (&builder)
+            .into_iter()
+            .take(512)
+            .map(Result::unwrap)
+            .collect();
+        assert!(ids.windows(2).all(|w| w[0] < w[1]));
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn puid_builder_transform_test() {
+        let builder = Puid::builder()
+            .prefix("foo")
+            .unwrap()
+            .transform(|id| id.to_uppercase());
+
+        for id in (&builder).into_iter().take(5) {
+            let id = id.unwrap();
+            assert!(id.starts_with("FOO_"));
+            assert!(!id.chars().any(|c| c.is_ascii_lowercase()));
+        }
+        assert!(builder.build().unwrap().starts_with("FOO_"));
+    }
+
+    // Generates an ID, then the same one again under the given strategy.
+    fn dedup_collision(strategy: RetryStrategy) -> (String, PuidResult<String>) {
+        let first = seeded(Puid::builder().prefix("foo").unwrap(), 1)
+            .generate()
+            .unwrap();
+        let builder = Puid::builder()
+            .prefix("foo")
+            .unwrap()
+            .dedup(true)
+            .dedup_strategy(strategy);
+        let builder = seeded(builder, 1);
+        builder
+            .seen
+            .as_ref()
+            .unwrap()
+            .lock()
+            .unwrap()
+            .insert(first.clone());
+        (first, builder.generate())
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn puid_builder_dedup_bump_counter_test() {
+        let (first, second) = dedup_collision(RetryStrategy::BumpCounter);
+        let second = second.unwrap();
+        // "foo_" and the version, then 9 for the time and 7 for the counter
+        assert_eq!(first[5..14], second[5..14]);
+        assert_eq!(&first[14..21], "0000000");
+        assert_eq!(&second[14..21], "0000001");
+    }
+
+    #[test]
+    fn puid_builder_dedup_bump_entropy_test() {
+        let (first, second) = dedup_collision(RetryStrategy::BumpEntropy);
+        assert_eq!(second.unwrap().len(), first.len() + 1);
+
+        // A retry growing the entropy past the maximum gives up
+        let builder = || Puid::builder().prefix("foo").unwrap().entropy(MAX_ENTROPY);
+        let first = seeded(builder(), 1).generate().unwrap();
+        let builder = seeded(
+            builder()
+                .dedup(true)
+                .dedup_strategy(RetryStrategy::BumpEntropy),
+            1,
+        );
+        builder.seen.as_ref().unwrap().lock().unwrap().insert(first);
+        assert!(matches!(
+            builder.generate(),
+            Err(PuidError::EntropyTooLarge)
+        ));
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn puid_builder_dedup_advance_time_test() {
+        let (first, second) = dedup_collision(RetryStrategy::AdvanceTime);
+        let second = second.unwrap();
+        let time = |id: &str| from_base36(&id[5..14]).unwrap();
+        assert_eq!(time(&second), time(&first) + 1);
+    }
+
+    #[test]
+    fn puid_builder_dedup_fail_test() {
+        let (_, second) = dedup_collision(RetryStrategy::Fail);
+        assert!(matches!(second, Err(PuidError::Duplicate)));
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn puid_builder_ksuid_compat_test() {
+        let ksuid = |time| {
+            PuidBuilder {
+                time: Some(time),
+                ..Puid::builder().prefix("foo").unwrap()
+            }
+            .ksuid_compat()
+            .unwrap()
+        };
+
+        let a = ksuid(1651312057000);
+        let b = ksuid(1651312058000);
+        let c = ksuid(1751312057000);
+        assert_eq!(a.strip_prefix("foo_").unwrap().len(), KSUID_LEN);
+        assert!(a.chars().skip(4).all(|c| c.is_ascii_alphanumeric()));
+        assert!(a < b && b < c);
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn puid_builder_numeric_only_test() {
+        let ids: HashSet<String> = (0..1000)
+            .map(|_| Puid::builder().numeric_only().unwrap())
+            .collect();
+        assert_eq!(ids.len(), 1000);
+        assert!(ids.iter().all(|id| id.chars().all(|c| c.is_ascii_digit())));
+
+        let id = Puid::builder()
+            .prefix("42")
+            .unwrap()
+            .numeric_only()
+            .unwrap();
+        assert!(id.starts_with("421"));
+
+        let id = Puid::builder().prefix("foo").unwrap().numeric_only();
+        assert!(matches!(id, Err(PuidError::InvalidPrefix)));
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn puid_builder_include_thread_id_test() {
+        let builder = Puid::builder()
+            .prefix("foo")
+            .unwrap()
+            .include_thread_id(true);
+
+        let ids: Vec<String> = thread::scope(|s| {
+            let handles: Vec<_> = (0..8)
+                .map(|_| {
+                    s.spawn(|| {
+                        (&builder)
+                            .into_iter()
+                            .take(2000)
+                            .map(Result::unwrap)
+                            .collect::<Vec<_>>()
+                    })
+                })
+                .collect();
+            handles
+                .into_iter()
+                .flat_map(|h| h.join().unwrap())
+                .collect()
+        });
+
+        // "foo_", the version and flags, then 9 + 7 + 7 before the thread ID
+        let threads: HashSet<&str> = ids.iter().map(|id| &id[30..37]).collect();
+        assert_eq!(threads.len(), 8);
+        assert_eq!(ids.iter().collect::<HashSet<_>>().len(), ids.len());
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn puid_builder_build_cow_test() {
+        let id = Puid::builder().prefix("foo").unwrap().build_cow().unwrap();
+        assert!(matches!(id, Cow::Owned(_)));
+        assert!(id.starts_with("foo_1"));
+
+        let id = Puid::builder().placeholder("foo_test").build_cow().unwrap();
+        assert_eq!(id, Cow::Borrowed("foo_test"));
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn puid_builder_entry_points_validate_test() {
+        // Rejected by `build()`, though each ID could be written
+        let builder = || {
+            Puid::builder()
+                .prefix("foo")
+                .unwrap()
+                .entropy(300)
+                .self_describing(true)
+        };
+        assert!(matches!(
+            builder().build(),
+            Err(PuidError::EntropyOutOfRange)
+        ));
+        assert!(matches!(
+            builder().build_cow(),
+            Err(PuidError::EntropyOutOfRange)
+        ));
+        assert!(matches!(
+            builder().build_with_time(0),
+            Err(PuidError::EntropyOutOfRange)
+        ));
+        assert!(matches!(
+            builder().build_with_counter(0),
+            Err(PuidError::EntropyOutOfRange)
+        ));
+        assert!(matches!(
+            builder().build_redacted(),
+            Err(PuidError::EntropyOutOfRange)
+        ));
+        assert!(matches!(
+            builder().build_ordered_unique(1),
+            Err(PuidError::EntropyOutOfRange)
+        ));
+        assert!(matches!(
+            builder().build_into(&mut String::new()),
+            Err(PuidError::EntropyOutOfRange)
+        ));
+        let builder = || Puid::builder().prefix("foo").unwrap().max_total_len(8);
+        assert!(matches!(builder().ksuid_compat(), Err(PuidError::TooLong)));
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn puid_builder_counter_step_test() {
+        let counters = |start| {
+            let builder = Puid::builder()
+                .prefix("foo")
+                .unwrap()
+                .counter_step(2)
+                .counter_start(start);
+            // "foo_" and the version, then 9 characters of timestamp
+            (&builder)
+                .into_iter()
+                .take(300)
+                .map(|id| from_base36(&id.unwrap()[14..21]).unwrap())
+                .collect::<HashSet<_>>()
+        };
+
+        let even = counters(0);
+        let odd = counters(1);
+        assert_eq!(even.len(), 300);
+        assert_eq!(odd.len(), 300);
+        assert!(even.is_disjoint(&odd));
+        assert!(even.iter().all(|c| c % 2 == 0));
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn puid_builder_build_ordered_unique_test() {
+        let mut builder = Puid::builder().prefix("foo").unwrap();
+        builder.time = Some(1651312057000);
+
+        let ids = builder.build_ordered_unique(1000).unwrap();
+        assert_eq!(ids.len(), 1000);
+        assert!(ids.windows(2).all(|w| w[0] < w[1]));
+        assert_eq!(ids.iter().collect::<HashSet<_>>().len(), 1000);
+
+        // A clock moving every millisecond within one tick of a second
+        let ms = AtomicU64::new(1_651_312_057_000);
+        let clock = move || u128::from(ms.fetch_add(1, Ordering::SeqCst));
+        let builder = || {
+            Puid::builder()
+                .prefix("foo")
+                .unwrap()
+                .resolution(TimeResolution::Seconds)
+                .entropy(0)
+        };
+        for builder in [builder().clock(clock), builder().monotonic_only()] {
+            let ids = builder.build_ordered_unique(20000).unwrap();
+            assert!(ids.windows(2).all(|w| w[0] < w[1]));
+            assert_eq!(ids.iter().collect::<HashSet<_>>().len(), 20000);
+        }
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn puid_builder_bulk_random_test() {
+        let builder = Puid::builder().prefix("foo").unwrap().entropy(64);
+        let id = builder.bulk_random(true).build().unwrap();
+        let tail = &id[id.len() - 64..];
+        assert_eq!(id.len(), 28 + 64);
+        assert!(tail.chars().all(|c| c.is_ascii_alphanumeric()));
+
+        let id = Puid::builder()
+            .prefix("foo")
+            .unwrap()
+            .entropy(64)
+            .tail_alphabet("xyz")
+            .unwrap()
+            .bulk_random(true)
+            .build()
+            .unwrap();
+        assert!(id[id.len() - 64..].chars().all(|c| "xyz".contains(c)));
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn puid_builder_build_with_time_test() {
+        let id = Puid::builder()
+            .prefix("foo")
+            .unwrap()
+            .build_with_time(1651312057000)
+            .unwrap();
+        assert!(id.starts_with(&format!("foo_10{}", to_base36(1651312057000))));
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn puid_builder_build_with_counter_test() {
+        let id = Puid::builder()
+            .prefix("foo")
+            .unwrap()
+            .build_with_counter(200)
+            .unwrap();
+        assert_eq!(Puid::parse_lenient(&id).unwrap().counter, Some(200));
+
+        let a = seeded(Puid::builder().prefix("foo").unwrap(), 7).build_with_counter(9);
+        let b = seeded(Puid::builder().prefix("foo").unwrap(), 7).build_with_counter(9);
+        assert_eq!(a.unwrap(), b.unwrap());
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn puid_builder_charset_test() {
+        let id = Puid::builder()
+            .prefix("foo")
+            .unwrap()
+            .entropy(64)
+            .charset(CharSet::Base64Url)
+            .build()
+            .unwrap();
+        assert!(id.starts_with("foo.1"));
+        assert!(id[23..].chars().all(|c| BASE_64_URL.contains(c)));
+        assert!(Puid::is_url_safe(&id));
+        assert!(!Puid::is_url_safe("foo/bar+"));
+
+        let id = Puid::builder()
+            .prefix("foo")
+            .unwrap()
+            .separator('~')
+            .charset(CharSet::Base64Url)
+            .build()
+            .unwrap();
+        assert!(id.starts_with("foo~1"));
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn puid_builder_with_flavor_test() {
+        let build = |flavor| {
+            let builder = Puid::builder_with_flavor(flavor).prefix("foo").unwrap();
+            builder.build().unwrap()
+        };
+        let sorted = |flavor| {
+            let builder = Puid::builder_with_flavor(flavor).prefix("foo").unwrap();
+            let ids: Vec<_> = builder.iter().unwrap().take(100).collect();
+            ids.windows(2).all(|w| w[0] < w[1])
+        };
+
+        assert_eq!(
+            build(Flavor::Default).len(),
+            28 + usize::from(DEFAULT_ENTROPY)
+        );
+
+        let id = build(Flavor::StripeLike);
+        assert_eq!(id.len(), 28 + 24);
+        assert!(id[28..].chars().all(|c| c.is_ascii_alphanumeric()));
+
+        let id = build(Flavor::NanoidLike);
+        assert!(id.starts_with("foo.1"));
+        assert_eq!(id.len(), 28 + 21);
+        assert!(id[28..].chars().all(|c| BASE_64_URL.contains(c)));
+
+        let id = build(Flavor::UlidLike);
+        assert_eq!(id.len(), 8 + 17 + 16);
+        assert!(id[8..].chars().all(|c| CROCKFORD.contains(c)));
+        assert!(sorted(Flavor::UlidLike));
+
+        let id = build(Flavor::KsuidLike);
+        assert_eq!(id.len(), 8 + 16 + 22);
+        let ms = Puid::timestamp_of(&id).unwrap();
+        assert_eq!(ms % 1000, 0);
+        assert!(sorted(Flavor::KsuidLike));
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn puid_builder_qr_alphanumeric_test() {
+        let builder = || Puid::builder().prefix("FOO").unwrap().qr_alphanumeric();
+        for _ in 0..100 {
+            let id = builder().entropy(64).build().unwrap();
+            assert!(id.starts_with("FOO-1"));
+            assert!(id.chars().all(|c| QR_ALPHABET.contains(c)));
+            assert!(!id[5..].contains('-'));
+        }
+        let id = Puid::builder()
+            .prefix("FOO")
+            .unwrap()
+            .separator(':')
+            .qr_alphanumeric()
+            .build()
+            .unwrap();
+        assert!(id.starts_with("FOO:1"));
+        assert!(id.chars().all(|c| QR_ALPHABET.contains(c)));
+
+        // The IDs are not parsed back
+        let id = builder().clock(|| 1_651_312_057_000).build().unwrap();
+        assert!(matches!(Puid::validate_id(&id), Err(PuidError::Malformed)));
+        assert!(Puid::parse(&id).is_err());
+        assert_eq!(Puid::detect_format(&id).unwrap().encoding, Encoding::Base62);
+
+        assert!(matches!(
+            Puid::builder()
+                .prefix("foo")
+                .unwrap()
+                .qr_alphanumeric()
+                .build(),
+            Err(PuidError::InvalidPrefix)
+        ));
+        assert!(matches!(
+            builder().separator(':').build(),
+            Err(PuidError::SeparatorCollision)
+        ));
+        assert!(matches!(
+            Puid::builder()
+                .prefix("FOO")
+                .unwrap()
+                .separator('_')
+                .qr_alphanumeric()
+                .build(),
+            Err(PuidError::InvalidSeparator)
+        ));
+        assert!(matches!(
+            builder().case(Case::Lower).build(),
+            Err(PuidError::InvalidAlphabet)
+        ));
+        assert!(matches!(
+            builder().opaque(true).build(),
+            Err(PuidError::ConflictingOptions)
+        ));
+        assert!(matches!(
+            builder().locale("en").unwrap().build(),
+            Err(PuidError::ConflictingOptions)
+        ));
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn puid_builder_ocr_safe_test() {
+        let builder = Puid::builder()
+            .prefix("foo")
+            .unwrap()
+            .entropy(16)
+            .ocr_safe();
+        let id = builder.build().unwrap();
+        // The timestamp takes 11 characters of the smaller alphabet
+        assert_eq!(id.len(), 30 + 16 + 1);
+        // The version marker stays base-36 so parsers can read it
+        assert_eq!(&id[4..5], "1");
+        assert!(id[5..].chars().all(|c| OCR_ALPHABET.contains(c)));
+        assert!(Puid::verify_check_char(&id));
+
+        for at in 5..id.len() {
+            let c = id.as_bytes()[at];
+            let other = OCR_ALPHABET.bytes().find(|&o| o != c).unwrap();
+            let mut tampered = id.clone().into_bytes();
+            tampered[at] = other;
+            let tampered = String::from_utf8(tampered).unwrap();
+            assert!(!Puid::verify_check_char(&tampered), "{tampered}");
+        }
+        assert!(!Puid::verify_check_char("foo"));
+        assert!(!Puid::verify_check_char("foo_"));
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn puid_builder_case_test() {
+        let builder = || Puid::builder().prefix("foo").unwrap().entropy(64);
+        let id = builder().case(Case::Lower).build().unwrap();
+        assert!(id[28..]
+            .chars()
+            .all(|c| c.is_ascii_digit() || c.is_ascii_lowercase()));
+
+        let id = builder().case(Case::Upper).build().unwrap();
+        assert!(id[28..]
+            .chars()
+            .all(|c| c.is_ascii_digit() || c.is_ascii_uppercase()));
+
+        let id = builder()
+            .case(Case::Lower)
+            .case(Case::Mixed)
+            .build()
+            .unwrap();
+        assert_eq!(id.len(), 28 + 64);
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn puid_builder_build_many_with_parts_test() {
+        let builder = Puid::builder().prefix("foo").unwrap().tag(7);
+        let batch = builder.build_many_with_parts(1000).unwrap();
+        assert_eq!(batch.len(), 1000);
+        for (id, parts) in &batch {
+            assert_eq!(&Puid::parse(id).unwrap(), parts);
+        }
+        let unique: HashSet<_> = batch.iter().map(|(id, _)| id).collect();
+        assert_eq!(unique.len(), batch.len());
+
+        let batch = Puid::builder()
+            .prefix("foo")
+            .unwrap()
+            .include_pid(false)
+            .iso_timestamp(true)
+            .build_many_with_parts(10)
+            .unwrap();
+        for (id, parts) in &batch {
+            assert_eq!(parts.process_id, None);
+            assert_eq!(&Puid::parse(id).unwrap(), parts);
+        }
+        assert!(Puid::builder().build_many_with_parts(1).is_err());
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn puid_builder_opaque_test() {
+        let builder = Puid::builder().prefix("foo").unwrap().opaque(true);
+        let ids: Vec<String> = (&builder)
+            .into_iter()
+            .take(100)
+            .map(Result::unwrap)
+            .collect();
+        assert!(ids.iter().all(|id| id.len() == "foo_".len() + OPAQUE_LEN));
+        assert_eq!(ids.iter().collect::<HashSet<_>>().len(), 100);
+
+        let opaque = |seed| {
+            seeded(Puid::builder().prefix("foo").unwrap().opaque(true), seed)
+                .build()
+                .unwrap()
+        };
+        assert_eq!(opaque(1), opaque(1));
+        assert_ne!(opaque(1), opaque(2));
+
+        let (_, redacted) = Puid::builder()
+            .prefix("foo")
+            .unwrap()
+            .opaque(true)
+            .build_redacted()
+            .unwrap();
+        assert_eq!(redacted, "foo_****");
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn puid_builder_validate_test() {
+        let builder = Puid::builder().prefix("foo").unwrap();
+        assert!(builder.validate().is_ok());
+        assert_eq!(builder.max_len(), builder.build().unwrap().len());
+
+        let builder = Puid::builder()
+            .prefix("foo")
+            .unwrap()
+            .readable()
+            .tag(1)
+            .include_thread_id(true)
+            .self_describing(true)
+            .length_header(true);
+        assert!(builder.validate().is_ok());
+        assert_eq!(builder.max_len(), builder.build().unwrap().len());
+
+        let builder = Puid::builder().prefix("foo").unwrap().separator('a');
+        assert!(matches!(
+            builder.validate(),
+            Err(PuidError::SeparatorCollision)
+        ));
+        let builder = Puid::builder().prefix("foo").unwrap().max_total_len(20);
+        assert!(matches!(builder.validate(), Err(PuidError::TooLong)));
+        assert!(matches!(
+            Puid::builder().validate(),
+            Err(PuidError::InvalidPrefix)
+        ));
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn puid_builder_namespace_test() {
+        let burst = |ns| {
+            let builder = seeded(Puid::builder().prefix("foo").unwrap(), 7).namespace(ns);
+            (&builder)
+                .into_iter()
+                .take(500)
+                .map(Result::unwrap)
+                .collect::<HashSet<_>>()
+        };
+
+        let eu = burst("eu");
+        let us = burst("us");
+        assert_eq!(eu.len(), 500);
+        assert!(eu.is_disjoint(&us));
+        assert!(eu.iter().all(|id| id.starts_with("foo_20w")));
+        assert!(eu
+            .iter()
+            .all(|id| Puid::parse_lenient(id).unwrap().version == Some(2)));
+        assert_eq!(eu, burst("eu"));
+
+        // The namespace is mixed into the seed whichever is set first
+        let ids = |builder: PuidBuilder<'_>| {
+            let builder = PuidBuilder {
+                time: Some(1651312057000),
+                counter: Some(AtomicU32::new(0)),
+                ..builder
+            };
+            (&builder)
+                .into_iter()
+                .take(100)
+                .map(Result::unwrap)
+                .collect::<Vec<_>>()
+        };
+        let builder = || Puid::builder().prefix("foo").unwrap();
+        let before = ids(builder().namespace("eu").seed(7));
+        assert_eq!(before, ids(builder().seed(7).namespace("eu")));
+        assert_ne!(before, ids(builder().namespace("us").seed(7)));
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn puid_builder_node_id_test() {
+        let builder = Puid::builder().prefix("foo").unwrap().entropy(12);
+        assert_eq!(builder.build().unwrap().len(), 28 + 12);
+
+        let builder = Puid::builder()
+            .prefix("foo")
+            .unwrap()
+            .entropy(12)
+            .node_id(1295);
+        let id = builder.build().unwrap();
+        assert!(id.starts_with("foo_274"));
+        assert_eq!(id.len(), 34 + 12);
+        assert_eq!(&id[30..34], "00zz");
+
+        let inferred = PuidBuilder::infer_from(&id).unwrap();
+        assert_eq!(inferred.node_id, Some(1295));
+        assert_eq!(inferred.build().unwrap().len(), id.len());
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn puid_builder_clock_test() {
+        let builder = Puid::builder()
+            .prefix("foo")
+            .unwrap()
+            .clock(|| 1_651_312_057_000);
+        let id = builder.build().unwrap();
+        assert!(id.starts_with("foo_10l2lok9t4"));
+        assert_eq!(Puid::timestamp_of(&id).unwrap(), 1_651_312_057_000);
+
+        let now = AtomicU64::new(1_651_312_057_000);
+        let builder = Puid::builder()
+            .prefix("foo")
+            .unwrap()
+            .clock(|| u128::from(now.load(Ordering::SeqCst)));
+        let a = builder.generate().unwrap();
+        now.fetch_add(1, Ordering::SeqCst);
+        let b = builder.generate().unwrap();
+        assert!(a[..14] < b[..14]);
+        assert_eq!(Puid::timestamp_of(&b).unwrap(), 1_651_312_057_001);
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn puid_builder_gen_until_test() {
+        let builder = Puid::builder().prefix("foo").unwrap();
+        // A virtual clock advancing a millisecond per reading
+        let start = Instant::now();
+        let mut elapsed = Duration::ZERO;
+        let mut now = || {
+            let instant = start + elapsed;
+            elapsed += Duration::from_millis(1);
+            instant
+        };
+        let ids = builder
+            .gen_until_with(start + Duration::from_millis(10), &mut now)
+            .unwrap();
+        assert_eq!(ids.len(), 10);
+        let unique: HashSet<_> = ids.iter().collect();
+        assert_eq!(unique.len(), ids.len());
+
+        assert!(builder.gen_until(start).unwrap().is_empty());
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn puid_builder_build_with_random_bytes_test() {
+        let builder = Puid::builder().prefix("foo").unwrap().entropy(12);
+        let (id, bytes) = builder.build_with_random_bytes().unwrap();
+        assert_eq!(id.len(), 28 + 12);
+        assert_eq!(bytes.len(), 8);
+        let digits: Vec<char> = BASE_62.iter().copied().map(char::from).collect();
+        assert_eq!(encode_bytes(&bytes, &digits, 12), id[28..]);
+
+        let builder = Puid::builder()
+            .prefix("foo")
+            .unwrap()
+            .entropy(16)
+            .charset(CharSet::Base64Url);
+        let (id, bytes) = builder.build_with_random_bytes().unwrap();
+        assert_eq!(bytes.len(), 12);
+        assert!(id[28..].chars().all(|c| BASE_64_URL.contains(c)));
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn puid_builder_crockford_test() {
+        let builder = Puid::builder()
+            .prefix("foo")
+            .unwrap()
+            .entropy(12)
+            .crockford_base32(true);
+        let id = builder.build().unwrap();
+        assert!(id.starts_with("foo_335s"));
+        assert_eq!(id.len(), 32 + 12);
+        assert!(id[8..32].chars().all(|c| CROCKFORD.contains(c)));
+        assert_eq!(
+            Puid::detect_format(&id).unwrap().encoding,
+            Encoding::Crockford
+        );
+
+        let ms = Puid::timestamp_of(&id).unwrap();
+        assert!(time() - ms < 1000);
+        // Lowercase and misread digits decode the same
+        let typed = format!("foo_335s{}", id[8..].to_lowercase().replace('1', "l"));
+        assert_eq!(Puid::timestamp_of(&typed).unwrap(), ms);
+
+        let inferred = PuidBuilder::infer_from(&id).unwrap();
+        assert!(inferred.crockford);
+        assert_eq!(inferred.build().unwrap().len(), id.len());
+    }
+
+    #[test]
+    fn base32_crockford_test() {
+        assert_eq!(to_base32_crockford(1_651_312_057_000), "1G1WXWPN8");
+        assert_eq!(from_base32_crockford("1G1WXWPN8"), Some(1_651_312_057_000));
+        assert_eq!(
+            from_base32_crockford("ig1wxwpn8"),
+            from_base32_crockford("1G1WXWPN8")
+        );
+        assert_eq!(from_base32_crockford("O"), Some(0));
+        assert_eq!(from_base32_crockford("U"), None);
+        assert_eq!(from_base32_crockford(""), None);
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn puid_builder_entropy_range_test() {
+        let builder = Puid::builder()
+            .prefix("foo")
+            .unwrap()
+            .entropy_range(8, 16)
+            .unwrap();
+        let lens: HashSet<_> = (&builder)
+            .into_iter()
+            .take(500)
+            .map(|id| id.unwrap().len() - 28)
+            .collect();
+        assert!(lens.iter().all(|len| (8..=16).contains(len)));
+        assert!(lens.len() > 1);
+
+        assert!(matches!(
+            Puid::builder().entropy_range(16, 8),
+            Err(PuidError::InvalidEntropyRange)
+        ));
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn puid_builder_resolution_test() {
+        let build = |resolution| {
+            seeded(Puid::builder().prefix("foo").unwrap(), 7)
+                .entropy(12)
+                .resolution(resolution)
+                .build()
+                .unwrap()
+        };
+
+        let millis = build(TimeResolution::Millis);
+        assert!(millis.starts_with("foo_1"));
+        assert_eq!(millis.len(), 28 + 12);
+
+        let seconds = build(TimeResolution::Seconds);
+        assert_eq!(&seconds[..16], "foo_2e8000rb5cjd");
+        assert_eq!(seconds.len(), 30 + 12);
+
+        let micros = build(TimeResolution::Micros);
+        assert!(micros.starts_with("foo_2e820g9caab4gn4"));
+        assert_eq!(micros.len(), 33 + 12);
+
+        for id in [millis, seconds, micros] {
+            assert_eq!(Puid::timestamp_of(&id).unwrap(), 1651312057000);
+            assert_eq!(Puid::detect_format(&id).unwrap().encoding, Encoding::Base36);
+            let inferred = PuidBuilder::infer_from(&id).unwrap();
+            assert_eq!(inferred.build().unwrap().len(), id.len());
+        }
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn puid_builder_include_pid_test() {
+        let builder = Puid::builder()
+            .prefix("foo")
+            .unwrap()
+            .entropy(12)
+            .include_pid(false);
+        let id = builder.build().unwrap();
+        assert!(id.starts_with("foo_2sg"));
+        assert_eq!(id.len(), 28 - 7 + 2 + 12);
+        let parts = Puid::parse(&id).unwrap();
+        assert_eq!(parts.process_id, None);
+        assert_eq!(parts.random.len(), 12);
+
+        // Flags outgrowing two characters widen the flags field
+        let builder = Puid::builder()
+            .prefix("foo")
+            .unwrap()
+            .entropy(12)
+            .include_pid(false)
+            .node_id(7)
+            .tag(7)
+            .length_header(true)
+            .self_describing(true);
+        assert_eq!(builder.layout_version(), Some(WIDE_VERSION));
+        let flags = builder.flags();
+        let id = builder.build().unwrap();
+        assert!(id.starts_with("foo_3"));
+        assert_eq!(Puid::tag_of(&id).unwrap(), 7);
+        let inferred = PuidBuilder::infer_from(&id).unwrap();
+        assert_eq!(inferred.flags(), flags);
+        assert_eq!(inferred.build().unwrap().len(), id.len());
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn puid_builder_installation_id_test() {
+        let builder = || Puid::builder().prefix("foo").unwrap();
+        for installation in [0, 42, u64::MAX] {
+            let id = builder().installation_id(installation).build().unwrap();
+            assert_eq!(Puid::installation_of(&id).unwrap(), installation);
+            assert_eq!(id.len(), 28 + 3 + 13 + usize::from(DEFAULT_ENTROPY));
+            let inferred = PuidBuilder::infer_from(&id).unwrap();
+            assert_eq!(inferred.installation_id, Some(installation));
+        }
+
+        let id = builder()
+            .installation_id(42)
+            .node_id(7)
+            .crockford_base32(true)
+            .delimited_fields(true)
+            .build()
+            .unwrap();
+        assert_eq!(Puid::installation_of(&id).unwrap(), 42);
+
+        let id = builder().build().unwrap();
+        assert!(matches!(
+            Puid::installation_of(&id),
+            Err(PuidError::Malformed)
+        ));
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn puid_builder_no_timestamp_test() {
+        let builder = || Puid::builder().prefix("foo").unwrap().no_timestamp();
+        let id = builder().build().unwrap();
+        assert!(id.starts_with("foo_36bk"));
+        assert_eq!(id.len(), 28 - 9 + 3 + usize::from(DEFAULT_ENTROPY));
+        assert!(matches!(Puid::timestamp_of(&id), Err(PuidError::Malformed)));
+        let parts = Puid::parse(&id).unwrap();
+        assert_eq!(parts.timestamp_ms, None);
+        assert_eq!(parts.random.len(), usize::from(DEFAULT_ENTROPY));
+        assert_eq!(
+            Puid::detect_format(&id).unwrap().version,
+            Some(WIDE_VERSION)
+        );
+        let inferred = PuidBuilder::infer_from(&id).unwrap().build().unwrap();
+        assert_eq!(inferred.len(), id.len());
+        assert!(Puid::timestamp_of(&inferred).is_err());
+
+        let id = builder().delimited_fields(true).build().unwrap();
+        assert!(Puid::parse(&id).unwrap().timestamp_ms.is_none());
+        assert!(matches!(
+            builder().sortable(true).build(),
+            Err(PuidError::Unsortable)
+        ));
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn puid_builder_per_second_counter_test() {
+        let mut builder = Puid::builder()
+            .prefix("foo")
+            .unwrap()
+            .per_second_counter(true);
+        let mut counter_at = |ms| {
+            builder.time = Some(ms);
+            let id = (&builder).into_iter().next().unwrap().unwrap();
+            Puid::parse(&id).unwrap().counter.unwrap()
+        };
+
+        assert_eq!(counter_at(1651312057000), 0);
+        assert_eq!(counter_at(1651312057500), 1);
+        assert_eq!(counter_at(1651312057999), 2);
+        assert_eq!(counter_at(1651312058000), 0);
+        assert_eq!(counter_at(1651312058001), 1);
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn puid_builder_iter_test() {
+        let ids: Vec<_> = Puid::builder()
+            .prefix("foo")
+            .unwrap()
+            .iter()
+            .unwrap()
+            .take(1000)
+            .collect();
+        assert_eq!(ids.len(), 1000);
+        assert_eq!(ids.iter().collect::<HashSet<_>>().len(), 1000);
+        assert!(ids.iter().all(|id| id.starts_with("foo_1")));
+
+        let builder = Puid::builder()
+            .prefix("foo")
+            .unwrap()
+            .entropy(MAX_ENTROPY + 1);
+        assert!(matches!(builder.iter(), Err(PuidError::EntropyOutOfRange)));
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn puid_builder_build_into_test() {
+        let builder = Puid::builder().prefix("foo").unwrap();
+        let mut buf = String::from("stale");
+        builder.build_into(&mut buf).unwrap();
+        assert!(buf.starts_with("foo_1"));
+        assert_eq!(buf.len(), 28 + usize::from(DEFAULT_ENTROPY));
+
+        let allocated = crate::tracking::allocations(|| {
+            for _ in 0..1000 {
+                builder.build_into(&mut buf).unwrap();
+            }
+        });
+        assert_eq!(allocated, 0);
+
+        let mut ids = HashSet::new();
+        for _ in 0..1000 {
+            builder.build_into(&mut buf).unwrap();
+            ids.insert(buf.clone());
+        }
+        assert_eq!(ids.len(), 1000);
+
+        // Measuring the length for the cap only happens on the first call
+        let builder = builder.max_total_len(64);
+        builder.build_into(&mut buf).unwrap();
+        let allocated = crate::tracking::allocations(|| {
+            for _ in 0..1000 {
+                builder.build_into(&mut buf).unwrap();
+            }
+        });
+        assert_eq!(allocated, 0);
+
+        // Setting an option validates again
+        let builder = builder.entropy(MAX_ENTROPY + 1);
+        assert!(matches!(
+            builder.build_into(&mut buf),
+            Err(PuidError::EntropyOutOfRange)
+        ));
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn puid_builder_max_entropy_test() {
+        let builder = Puid::builder().prefix("foo").unwrap().entropy(MAX_ENTROPY);
+        assert_eq!(builder.build().unwrap().len(), 28 + MAX_ENTROPY);
+
+        let id = Puid::builder()
+            .prefix("foo")
+            .unwrap()
+            .entropy(512)
+            .build()
+            .unwrap();
+        assert_eq!(id.len(), 28 + 512);
+        assert_eq!(Puid::parse(&id).unwrap().random.len(), 512);
+
+        let id = Puid::builder()
+            .prefix("foo")
+            .unwrap()
+            .entropy(0)
+            .build()
+            .unwrap();
+        assert_eq!(id.len(), 28);
+        assert!(Puid::parse(&id).unwrap().random.is_empty());
+
+        let builder = Puid::builder()
+            .prefix("foo")
+            .unwrap()
+            .entropy(MAX_ENTROPY + 1);
+        assert!(matches!(
+            builder.validate(),
+            Err(PuidError::EntropyOutOfRange)
+        ));
+        assert!(matches!(builder.build(), Err(PuidError::EntropyOutOfRange)));
+
+        let builder = Puid::builder()
+            .prefix("foo")
+            .unwrap()
+            .self_describing(true)
+            .entropy(256);
+        assert!(matches!(builder.build(), Err(PuidError::EntropyOutOfRange)));
+
+        let id = Puid::builder()
+            .prefix("foo")
+            .unwrap()
+            .tail_alphabet("αβγ")
+            .unwrap()
+            .build()
+            .unwrap();
+        assert!(id.ends_with(['α', 'β', 'γ']));
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn puid_builder_iso_timestamp_test() {
+        let id = Puid::builder()
+            .prefix("foo")
+            .unwrap()
+            .iso_timestamp(true)
+            .build_with_time(1718187300123)
+            .unwrap();
+        assert_eq!(&id[4..7], "21s");
+        assert_eq!(&id[7..25], "20240612T101500123");
+        assert_eq!(id.len(), 4 + 3 + 18 + 7 + 7 + usize::from(DEFAULT_ENTROPY));
+
+        let parts = Puid::parse(&id).unwrap();
+        assert_eq!(parts.timestamp_ms, Some(1718187300123));
+        assert_eq!(parts.random.len(), usize::from(DEFAULT_ENTROPY));
+        let other = PuidBuilder::infer_from(&id).unwrap().build().unwrap();
+        assert_eq!(other.len(), id.len());
+
+        let builder = Puid::builder()
+            .prefix("foo")
+            .unwrap()
+            .iso_timestamp(true)
+            .scramble(true);
+        assert!(matches!(
+            builder.build(),
+            Err(PuidError::ConflictingOptions)
+        ));
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn puid_builder_sortable_test() {
+        // Times on both sides of the base-36 timestamp gaining a digit
+        let mut ids: Vec<String> = [36u128.pow(8) - 1, 36u128.pow(8), 36u128.pow(9) - 1]
+            .into_iter()
+            .map(|ms| {
+                let builder = Puid::builder().prefix("foo").unwrap().sortable(true);
+                builder.build_with_time(ms).unwrap()
+            })
+            .collect();
+        let ordered = ids.clone();
+        ids.sort();
+        assert_eq!(ids, ordered);
+        assert!(ids.iter().all(|id| id.len() == ids[0].len()));
+
+        let mut builder = Puid::builder().prefix("foo").unwrap().sortable(true);
+        builder.time = Some(1651312057000);
+        let ids = builder.build_ordered_unique(300).unwrap();
+        assert!(ids.windows(2).all(|w| w[0] < w[1]));
+
+        let builder = Puid::builder().prefix("foo").unwrap().sortable(true);
+        assert!(matches!(
+            builder.scramble(true).build(),
+            Err(PuidError::Unsortable)
+        ));
+        let builder = Puid::builder().prefix("foo").unwrap().sortable(true);
+        let builder = builder.numeric_alphabet("9876543210").unwrap();
+        assert!(matches!(builder.validate(), Err(PuidError::Unsortable)));
+        let builder = Puid::builder().prefix("foo").unwrap().sortable(true);
+        assert!(builder.readable().build().is_ok());
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn puid_builder_delimited_fields_test() {
+        let builder = Puid::builder()
+            .prefix("foo")
+            .unwrap()
+            .delimited_fields(true);
+        let id = builder.build_with_time(1651312057000).unwrap();
+        // The prefix, the version marker, the timestamp, counter, process ID and random
+        let fields: Vec<&str> = id.split('_').collect();
+        assert_eq!(fields.len(), 6);
+        assert_eq!(fields[1], "23k");
+        assert_eq!(id.len(), 28 + 2 + 4 + usize::from(DEFAULT_ENTROPY));
+
+        let parts = Puid::parse(&id).unwrap();
+        assert_eq!(parts.timestamp_ms, Some(1651312057000));
+        assert_eq!(parts.random, fields[5]);
+        let other = PuidBuilder::infer_from(&id).unwrap().build().unwrap();
+        assert_eq!(other.split('_').count(), 6);
+
+        let builder = Puid::builder()
+            .prefix("foo")
+            .unwrap()
+            .separator('-')
+            .delimited_fields(true)
+            .tag(7)
+            .scramble(true);
+        let id = builder.build().unwrap();
+        assert_eq!(id.split('-').count(), 7);
+        assert_eq!(Puid::tag_of(&Puid::unscramble(&id).unwrap()).unwrap(), 7);
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn puid_builder_reserve_test() {
+        let batch = |builder: &PuidBuilder| {
+            let mut ids = Vec::new();
+            let allocations = crate::tracking::allocations(|| {
+                ids = builder
+                    .into_iter()
+                    .take(1000)
+                    .collect::<PuidResult<_>>()
+                    .unwrap();
+            });
+            (ids, allocations)
+        };
+        let builder = Puid::builder().prefix("foo").unwrap().dedup(true);
+        let (plain, plain_allocations) = batch(&seeded(builder, 42));
+        let builder = Puid::builder().prefix("foo").unwrap().dedup(true);
+        let (reserved, reserved_allocations) = batch(&seeded(builder.reserve(1000), 42));
+        assert_eq!(plain, reserved);
+        assert!(reserved_allocations < plain_allocations);
+    }
+
+    #[test]
+    fn puid_builder_missing_source_test() {
+        let builder = || Puid::builder().prefix("foo").unwrap();
+        let id = builder()
+            .clock(|| 1_651_312_057_000)
+            .seed(42)
+            .build()
+            .unwrap();
+        assert!(Puid::validate_id(&id).is_ok());
+        if cfg!(not(feature = "std")) {
+            assert!(matches!(builder().build(), Err(PuidError::MissingSource)));
+            assert!(matches!(
+                builder().seed(42).build(),
+                Err(PuidError::MissingSource)
+            ));
+        }
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn puid_builder_seed_test() {
+        let tail = |seed| {
+            let id = Puid::builder().prefix("foo").unwrap().seed(seed).build();
+            id.unwrap()[23..].to_string()
+        };
+        assert_eq!(tail(42), tail(42));
+        assert_ne!(tail(42), tail(43));
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn puid_builder_alphabet_test() {
+        let base58 = "123456789ABCDEFGHJKLMNPQRSTUVWXYZabcdefghijkmnopqrstuvwxyz";
+        let id = Puid::builder()
+            .prefix("foo")
+            .unwrap()
+            .entropy(64)
+            .alphabet(base58)
+            .unwrap()
+            .build()
+            .unwrap();
+        let random = &id[id.len() - 64..];
+        assert!(random.chars().all(|c| base58.contains(c)));
+
+        assert!(matches!(
+            Puid::builder().alphabet(""),
+            Err(PuidError::EmptyAlphabet)
+        ));
+        assert!(matches!(
+            Puid::builder().alphabet("a"),
+            Err(PuidError::InvalidAlphabet)
+        ));
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn puid_builder_min_prefix_len_test() {
+        assert!(Puid::builder().prefix("ab").is_ok());
+        let builder = Puid::builder().min_prefix_len(3).unwrap();
+        assert!(matches!(
+            builder.prefix("ab"),
+            Err(PuidError::InvalidPrefix)
+        ));
+        let builder = Puid::builder().min_prefix_len(3).unwrap();
+        assert!(builder.prefix("abc").is_ok());
+
+        let builder = Puid::builder().prefix("ab").unwrap().min_prefix_len(3);
+        assert!(matches!(
+            builder.unwrap().build(),
+            Err(PuidError::InvalidPrefix)
+        ));
+        assert!(Puid::builder().min_prefix_len(9).is_err());
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn puid_builder_no_prefix_test() {
+        assert!(matches!(
+            Puid::builder().entropy(12).build(),
+            Err(PuidError::InvalidPrefix)
+        ));
+        let id = Puid::builder().no_prefix().entropy(12).build().unwrap();
+        assert!(id.starts_with('1'));
+        assert_eq!(id.len(), 24 + 12);
+        assert!(id.chars().all(|c| c.is_ascii_alphanumeric()));
+
+        assert!(time() - Puid::timestamp_of(&id).unwrap() < 1000);
+        assert_eq!(Puid::parse(&id).unwrap().prefix, "");
+        assert_eq!(Puid::detect_format(&id).unwrap().version, Some(1));
+        let inferred = PuidBuilder::infer_from(&id).unwrap();
+        assert!(inferred.no_prefix);
+        assert_eq!(inferred.build().unwrap().len(), id.len());
+
+        let id = Puid::builder().no_prefix().prefix("foo").unwrap().build();
+        assert!(id.unwrap().starts_with("foo_1"));
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn puid_builder_max_prefix_len_test() {
+        assert!(Puid::builder().prefix("billingsvc").is_err());
+        let builder = Puid::builder().max_prefix_len(16).unwrap();
+        let id = builder.prefix("billingsvc").unwrap().build().unwrap();
+        assert!(id.starts_with("billingsvc_1"));
+        assert_eq!(id.len(), 35 + usize::from(DEFAULT_ENTROPY));
+        assert_eq!(
+            PuidBuilder::infer_from(&id).unwrap().build().unwrap().len(),
+            id.len()
+        );
+
+        let builder = Puid::builder().max_prefix_len(16).unwrap();
+        assert!(builder.prefix("billingsvc2025abc").is_err());
+        let builder = Puid::builder().prefix("abcdef").unwrap().max_prefix_len(4);
+        assert!(matches!(
+            builder.unwrap().build(),
+            Err(PuidError::InvalidPrefix)
+        ));
+        assert!(Puid::builder().max_prefix_len(33).is_err());
+        assert!(Puid::builder()
+            .min_prefix_len(3)
+            .unwrap()
+            .max_prefix_len(2)
+            .is_err());
+        assert!(Puid::builder()
+            .max_prefix_len(16)
+            .unwrap()
+            .min_prefix_len(12)
+            .is_ok());
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn puid_ephemeral_u64_test() {
+        let a = Puid::ephemeral_u64();
+        let b = Puid::ephemeral_u64();
+        assert_ne!(a, b);
+        assert!(b >> 8 >= a >> 8);
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn puid_builder_infer_from_test() {
+        let id = Puid::builder()
+            .prefix("foo")
+            .unwrap()
+            .separator('-')
+            .entropy(20)
+            .tag(7)
+            .build()
+            .unwrap();
+        let builder = PuidBuilder::infer_from(&id).unwrap();
+        let other = builder.build().unwrap();
+        assert_eq!(Puid::prefix_of(&other).unwrap(), "foo");
+        assert_eq!(other.len(), id.len());
+        assert_eq!(&other[..7], &id[..7]);
+        assert_eq!(Puid::tag_of(&other).unwrap(), 7);
+        assert_eq!(Puid::parse_lenient(&other).unwrap().random.len(), 20);
+
+        let id = Puid::builder()
+            .prefix("bar")
+            .unwrap()
+            .numeric_alphabet(std::str::from_utf8(BASE_62).unwrap())
+            .unwrap()
+            .build()
+            .unwrap();
+        let other = PuidBuilder::infer_from(&id).unwrap().build().unwrap();
+        assert_eq!(other.len(), id.len());
+
+        assert!(PuidBuilder::infer_from("foo_kc7lgen0000abcdef").is_err());
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn puid_builder_build_with_desc_key_test() {
+        let pairs: Vec<(String, String)> = (0..5)
+            .map(|i| {
+                let mut builder = Puid::builder().prefix("foo").unwrap();
+                builder.time = Some(1651312057000 + i * 1000);
+                builder.build_with_desc_key().unwrap()
+            })
+            .collect();
+
+        let mut ids: Vec<&String> = pairs.iter().map(|(id, _)| id).collect();
+        let mut keys: Vec<&String> = pairs.iter().map(|(_, key)| key).collect();
+        ids.sort();
+        keys.sort();
+        assert!(ids.iter().zip(pairs.iter()).all(|(a, (b, _))| *a == b));
+        assert!(keys
+            .iter()
+            .zip(pairs.iter().rev())
+            .all(|(a, (_, b))| *a == b));
+        assert_eq!(pairs[0].0.len(), pairs[0].1.len());
+
+        let builder = || Puid::builder().prefix("foo").unwrap();
+        for builder in [
+            builder().opaque(true),
+            builder().scramble(true),
+            builder().encrypted_sortable(b"secret"),
+            builder().iso_timestamp(true),
+            builder().no_timestamp(),
+        ] {
+            assert!(matches!(
+                builder.build_with_desc_key(),
+                Err(PuidError::Unsortable)
+            ));
+        }
+        assert!(matches!(
+            Puid::builder().build_with_desc_key(),
+            Err(PuidError::InvalidPrefix)
+        ));
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn puid_builder_try_entropy_test() {
+        let builder = Puid::builder().prefix("foo").unwrap().max_total_len(16);
+        assert!(matches!(builder.try_entropy(24), Err(PuidError::TooLong)));
+
+        let builder = Puid::builder().prefix("foo").unwrap().max_total_len(64);
+        assert!(builder.try_entropy(24).unwrap().build().is_ok());
+
+        // The cap counts every field, not only the prefix and random sequence
+        let builder = || Puid::builder().prefix("foo").unwrap().max_total_len(40);
+        let id = builder().try_entropy(12).unwrap().build().unwrap();
+        assert_eq!(id.len(), 40);
+        assert!(matches!(builder().try_entropy(13), Err(PuidError::TooLong)));
+        assert!(matches!(
+            builder().entropy(13).build(),
+            Err(PuidError::TooLong)
+        ));
+        let builder = || builder().tag(7);
+        assert!(matches!(builder().try_entropy(12), Err(PuidError::TooLong)));
+        assert!(builder().try_entropy(6).unwrap().build().is_ok());
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn puid_builder_max_total_len_test() {
+        let id = Puid::builder()
+            .prefix("foo")
+            .unwrap()
+            .max_total_len(8)
+            .build();
+        assert!(matches!(id, Err(PuidError::TooLong)));
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn puid_builder_max_bytes_test() {
+        let builder = || {
+            Puid::builder()
+                .prefix("foo")
+                .unwrap()
+                .entropy(12)
+                .max_bytes(48)
+        };
+        let id = builder().tail_alphabet("ab").unwrap().build().unwrap();
+        assert_eq!(id.len(), 28 + 12);
+
+        let id = builder().tail_alphabet("αβ").unwrap().build();
+        assert!(matches!(id, Err(PuidError::TooLong)));
+
+        // The character cap fits both prefixes, only the bytes differ
+        let builder = || {
+            Puid::builder()
+                .prefix_validator(|prefix| prefix.chars().all(char::is_alphanumeric))
+                .entropy(12)
+                .max_total_len(40)
+                .max_bytes(40)
+        };
+        let id = builder().prefix("foo").unwrap().build().unwrap();
+        assert_eq!(id.len(), 40);
+        let builder = builder().prefix("föö").unwrap();
+        assert!(builder.validate().is_ok());
+        assert!(matches!(builder.build(), Err(PuidError::TooLong)));
+        let id = Puid::builder()
+            .prefix_validator(|prefix| prefix.chars().all(char::is_alphanumeric))
+            .prefix("föö")
+            .unwrap()
+            .entropy(12)
+            .max_total_len(40)
+            .build()
+            .unwrap();
+        assert_eq!((id.chars().count(), id.len()), (40, 42));
+    }
+
+    #[cfg(feature = "testing")]
+    #[test]
+    fn puid_builder_deterministic_test() {
+        let ids = |seed| -> Vec<String> {
+            let builder = Puid::builder()
+                .prefix("foo")
+                .unwrap()
+                .entropy(12)
+                .deterministic(seed);
+            (0..3).map(|_| builder.compose().unwrap().0).collect()
+        };
+        assert_eq!(ids(42), ids(42));
+        assert_ne!(ids(42), ids(7));
+        // Without `std` the process ID is left out by default
+        if cfg!(feature = "std") {
+            assert_eq!(ids(42)[0], "foo_10l2lok9t400000000000001IhPi3oZCnaWv");
+        }
     }
 }
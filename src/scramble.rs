@@ -0,0 +1,129 @@
+use crate::{
+    errors::{PuidError, PuidResult},
+    format::{flags_len, Fields},
+    puid::{pad, to_base36, EXTENDED_VERSION, FLAGS_MAX, FLAG_SCRAMBLED, VERSION, WIDE_VERSION},
+    Puid,
+};
+use alloc::string::{String, ToString};
+
+/// Scrambles the fields of a freshly composed ID flagged as scrambled.
+pub(crate) fn scramble(id: &str) -> PuidResult<String> {
+    shift(id, true)
+}
+
+impl Puid {
+    /// Recovers the ordered form of a scrambled ID, identical to the ID
+    /// generated without scrambling.
+    ///
+    /// IDs that are not scrambled are returned unchanged. Returns
+    /// `PuidError::Malformed` if the ID does not match the layout.
+    pub fn unscramble(id: &str) -> PuidResult<String> {
+        let fields = Fields::split(id)?;
+        if fields.flags & FLAG_SCRAMBLED == 0 {
+            return Ok(id.to_string());
+        }
+        let id = shift(id, false)?;
+
+        // The version marker follows the prefix, locale and separators
+        let mut at = fields.prefix.len();
+        let separator = id[at..].chars().next().unwrap().len_utf8();
+        at += separator;
+        if let Some(locale) = fields.locale {
+            at += locale.len() + separator;
+        }
+        let flags = fields.flags & !FLAG_SCRAMBLED;
+        let marker = if flags == 0 {
+            VERSION.to_string()
+        } else if u128::from(flags) > FLAGS_MAX {
+            [
+                WIDE_VERSION.to_string(),
+                pad(to_base36(u128::from(flags)), 3, '0'),
+            ]
+            .concat()
+        } else {
+            [
+                EXTENDED_VERSION.to_string(),
+                pad(to_base36(u128::from(flags)), 2, '0'),
+            ]
+            .concat()
+        };
+        let end = at + 1 + flags_len(fields.version);
+        Ok([&id[..at], &marker, &id[end..]].concat())
+    }
+}
+
+/// Shifts every character of the fields by the value of the random character
+/// at the same position, cycling over the random sequence, forwards to
+/// scramble and backwards to unscramble.
+fn shift(id: &str, forward: bool) -> PuidResult<String> {
+    let fields = Fields::split(id)?;
+    let key = fields.random.as_bytes();
+    if key.is_empty() {
+        return Ok(id.to_string());
+    }
+
+    let offset = |s: &str| s.as_ptr() as usize - id.as_ptr() as usize;
+    let (start, end) = (offset(fields.timestamp), offset(fields.random));
+    let mut result = String::with_capacity(id.len());
+    result.push_str(&id[..start]);
+    for (i, c) in id[start..end].chars().enumerate() {
+        // Keeps the separators between delimited fields
+        if !c.is_ascii_alphanumeric() {
+            result.push(c);
+            continue;
+        }
+        let v = c.to_digit(36).ok_or(PuidError::Malformed)?;
+        let k = u32::from(key[i % key.len()]) % 36;
+        let v = if forward {
+            (v + k) % 36
+        } else {
+            (v + 36 - k) % 36
+        };
+        result.push(char::from_digit(v, 36).unwrap());
+    }
+    result.push_str(&id[end..]);
+    Ok(result)
+}
+
+#[cfg(all(test, feature = "std"))]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn scramble_test() {
+        let builder = Puid::builder().prefix("foo").unwrap().scramble(true);
+        let id = builder.build().unwrap();
+        assert!(id.starts_with("foo_208"));
+
+        let ordered = Puid::unscramble(&id).unwrap();
+        assert!(ordered.starts_with("foo_1"));
+        assert_eq!(ordered.len(), id.len() - 2);
+        assert_eq!(&ordered[ordered.len() - 12..], &id[id.len() - 12..]);
+        assert_eq!(Puid::unscramble(&ordered).unwrap(), ordered);
+    }
+
+    #[test]
+    fn scramble_round_trip_test() {
+        let id = Puid::builder()
+            .prefix("foo")
+            .unwrap()
+            .tag(7)
+            .length_header(true)
+            .build()
+            .unwrap();
+        let scrambled = shift(&id, true).unwrap();
+        assert_ne!(scrambled, id);
+        assert_eq!(shift(&scrambled, false).unwrap(), id);
+    }
+
+    #[test]
+    fn scramble_numeric_test() {
+        let builder = Puid::builder()
+            .prefix("foo")
+            .unwrap()
+            .numeric_alphabet("0123456789")
+            .unwrap()
+            .scramble(true);
+        assert!(matches!(builder.build(), Err(PuidError::InvalidAlphabet)));
+    }
+}
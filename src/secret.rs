@@ -0,0 +1,41 @@
+use crate::{
+    errors::{PuidError, PuidResult},
+    puid::rnd_string,
+    Puid,
+};
+use alloc::string::String;
+use rand::rngs::OsRng;
+
+// Bits carried by an alphanumeric character, log2(62)
+const BITS_PER_CHAR: f64 = 5.954_196_310_386_876;
+
+impl Puid {
+    /// Generates a standalone alphanumeric secret carrying at least `bits`
+    /// bits of entropy, e.g. for API keys kept apart from IDs.
+    ///
+    /// Characters are sampled without bias from the operating system random
+    /// generator. Returns `PuidError::TooLong` if the secret would exceed 255
+    /// characters.
+    pub fn secret(bits: u32) -> PuidResult<String> {
+        let len = (f64::from(bits) / BITS_PER_CHAR).ceil();
+        let len = u8::try_from(len as u32).map_err(|_| PuidError::TooLong)?;
+        Ok(rnd_string(&mut OsRng, usize::from(len)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn secret_test() {
+        let secret = Puid::secret(128).unwrap();
+        assert_eq!(secret.len(), 22);
+        assert!(secret.len() as f64 * BITS_PER_CHAR >= 128.0);
+        assert!(secret.chars().all(|c| c.is_ascii_alphanumeric()));
+        assert_ne!(secret, Puid::secret(128).unwrap());
+
+        assert_eq!(Puid::secret(256).unwrap().len(), 43);
+        assert!(Puid::secret(2000).is_err());
+    }
+}
@@ -0,0 +1,97 @@
+use crate::{
+    errors::{PuidError, PuidResult},
+    puid::{from_base36, to_base36, PuidBuilder},
+    Puid,
+};
+use alloc::{format, string::String};
+use hmac::{Hmac, Mac};
+use sha2::Sha256;
+
+type HmacSha256 = Hmac<Sha256>;
+
+// Delimiter between the ID and its tag
+const TAG_DELIMITER: char = '.';
+// Bytes of the HMAC kept in the tag
+const TAG_BYTES: usize = 8;
+// Base-36 characters needed for a `u64`
+const TAG_LEN: usize = 13;
+
+impl<'a> PuidBuilder<'a> {
+    /// Builds the PUID and appends a truncated HMAC-SHA256 tag of it.
+    ///
+    /// The tag covers the whole ID, prefix included, and is appended as
+    /// `.` followed by 13 base-36 characters.
+    pub fn sign(self, key: &[u8]) -> PuidResult<String> {
+        self.validate()?;
+        let mut id = self.generate()?;
+        let tag = u64::from_be_bytes(
+            mac(&id, key).finalize().into_bytes()[..TAG_BYTES]
+                .try_into()
+                .unwrap(),
+        );
+
+        id.push(TAG_DELIMITER);
+        id.push_str(&format!("{:0>TAG_LEN$}", to_base36(u128::from(tag))));
+        Ok(id)
+    }
+}
+
+impl Puid {
+    /// Verifies the tag of a PUID produced by `PuidBuilder::sign`.
+    ///
+    /// Returns `PuidError::Malformed` if the ID has no well-formed tag.
+    pub fn verify(id: &str, key: &[u8]) -> PuidResult<bool> {
+        let (body, tag) = id.rsplit_once(TAG_DELIMITER).ok_or(PuidError::Malformed)?;
+        if tag.len() != TAG_LEN {
+            return Err(PuidError::Malformed);
+        }
+        let tag = from_base36(tag)
+            .and_then(|t| u64::try_from(t).ok())
+            .ok_or(PuidError::Malformed)?;
+
+        Ok(mac(body, key)
+            .verify_truncated_left(&tag.to_be_bytes())
+            .is_ok())
+    }
+}
+
+/// Computes the HMAC of an ID with the given key.
+fn mac(id: &str, key: &[u8]) -> HmacSha256 {
+    let mut mac = HmacSha256::new_from_slice(key).expect("HMAC accepts keys of any length");
+    mac.update(id.as_bytes());
+    mac
+}
+
+#[cfg(all(test, feature = "std"))]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn sign_verify_test() {
+        let key = b"secret";
+        let id = Puid::builder().prefix("foo").unwrap().sign(key).unwrap();
+        assert!(id.starts_with("foo_"));
+        assert!(Puid::verify(&id, key).unwrap());
+        assert!(!Puid::verify(&id, b"other").unwrap());
+    }
+
+    #[test]
+    fn verify_tampered_test() {
+        let key = b"secret";
+        let id = Puid::builder().prefix("foo").unwrap().sign(key).unwrap();
+
+        let tampered = id.replacen("foo_", "bar_", 1);
+        assert!(!Puid::verify(&tampered, key).unwrap());
+
+        let (body, tag) = id.rsplit_once('.').unwrap();
+        let mut body = body.to_string();
+        let last = body.pop().unwrap();
+        body.push(if last == 'a' { 'b' } else { 'a' });
+        assert!(!Puid::verify(&format!("{body}.{tag}"), key).unwrap());
+
+        assert!(matches!(
+            Puid::verify(body.as_str(), key),
+            Err(PuidError::Malformed)
+        ));
+    }
+}
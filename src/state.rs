@@ -0,0 +1,130 @@
+use crate::{
+    errors::{PuidError, PuidResult},
+    puid::{from_base36, pad, to_base36, COUNTER, SEQUENCE},
+    Puid,
+};
+use core::{
+    fmt,
+    str::FromStr,
+    sync::atomic::{AtomicU32, AtomicU64, Ordering},
+};
+
+/// A snapshot of the global counter and monotonic sequence.
+///
+/// The state formats as a short string, e.g. `"2s.1a"`, and parses back with
+/// `str::parse`, so it can be written to a file on shutdown and read on start.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct PuidState {
+    counter: u32,
+    sequence: u64,
+}
+
+impl PuidState {
+    /// Returns the saved value of the global counter.
+    #[must_use]
+    pub fn counter(&self) -> u32 {
+        self.counter
+    }
+
+    /// Returns the saved value of the monotonic sequence.
+    #[must_use]
+    pub fn sequence(&self) -> u64 {
+        self.sequence
+    }
+}
+
+impl fmt::Display for PuidState {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{}.{}",
+            pad(to_base36(u128::from(self.counter)), 1, '0'),
+            pad(to_base36(u128::from(self.sequence)), 1, '0')
+        )
+    }
+}
+
+impl FromStr for PuidState {
+    type Err = PuidError;
+
+    fn from_str(s: &str) -> PuidResult<Self> {
+        let (counter, sequence) = s.split_once('.').ok_or(PuidError::Malformed)?;
+        let field = |s| from_base36(s).ok_or(PuidError::Malformed);
+        Ok(PuidState {
+            counter: u32::try_from(field(counter)?).map_err(|_| PuidError::Malformed)?,
+            sequence: u64::try_from(field(sequence)?).map_err(|_| PuidError::Malformed)?,
+        })
+    }
+}
+
+impl Puid {
+    /// Takes a snapshot of the global counter and monotonic sequence.
+    ///
+    /// The snapshot is only as durable as the moment it was taken: IDs
+    /// generated after the last export are not covered, so a crash can still
+    /// reuse their counter values. Export on shutdown, or periodically when
+    /// crashes matter.
+    #[must_use]
+    pub fn export_state() -> PuidState {
+        export(&COUNTER, &SEQUENCE)
+    }
+
+    /// Restores the global counter and monotonic sequence from a snapshot,
+    /// typically exported by a previous run.
+    ///
+    /// The sequence never moves backwards, so restoring an older state keeps
+    /// the values already reached by this process.
+    pub fn import_state(state: PuidState) {
+        import(&COUNTER, &SEQUENCE, state);
+    }
+}
+
+/// Reads a counter and sequence into a state.
+fn export(counter: &AtomicU32, sequence: &AtomicU64) -> PuidState {
+    PuidState {
+        counter: counter.load(Ordering::SeqCst),
+        sequence: sequence.load(Ordering::SeqCst),
+    }
+}
+
+/// Writes a state into a counter and sequence.
+fn import(counter: &AtomicU32, sequence: &AtomicU64, state: PuidState) {
+    counter.store(state.counter, Ordering::SeqCst);
+    sequence.fetch_max(state.sequence, Ordering::SeqCst);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloc::string::ToString;
+
+    #[test]
+    fn export_import_test() {
+        let counter = AtomicU32::new(100);
+        let sequence = AtomicU64::new(42);
+
+        let state = export(&counter, &sequence);
+        let saved: PuidState = state.to_string().parse().unwrap();
+        assert_eq!(saved, state);
+
+        counter.store(0, Ordering::SeqCst);
+        sequence.store(0, Ordering::SeqCst);
+        import(&counter, &sequence, saved);
+        assert_eq!(counter.load(Ordering::SeqCst), 100);
+        assert_eq!(sequence.load(Ordering::SeqCst), 42);
+    }
+
+    #[test]
+    fn puid_state_parse_test() {
+        assert_eq!(PuidState::default().to_string(), "0.0");
+        assert!("2s".parse::<PuidState>().is_err());
+        assert!("zzzzzzz.0".parse::<PuidState>().is_err());
+    }
+
+    #[test]
+    fn puid_state_global_test() {
+        let state = Puid::export_state();
+        Puid::import_state(state);
+        assert!(Puid::export_state().sequence() >= state.sequence());
+    }
+}